@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use inco_lightning::cpi::accounts::Allow;
+use inco_lightning::cpi::allow;
+use inco_lightning::types::Euint128;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::{COption, CustomError, IncoMint};
+
+// ========== ALLOWANCE HELPERS ==========
+
+/// Address of the Inco Lightning allowance PDA that lets `allowed` decrypt `handle`
+pub fn allowance_address(handle: Euint128, allowed: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[&handle.0.to_le_bytes(), allowed.as_ref()],
+        &INCO_LIGHTNING_ID,
+    ).0
+}
+
+/// Grant `allowed_pubkey` access to `handle` with accounts from remaining_accounts
+/// remaining_accounts[offset] = allowance_account (mut), the PDA [handle, allowed_pubkey]
+/// remaining_accounts[offset+1] = allowed_address (readonly), equal to allowed_pubkey
+/// Both accounts are checked against their expected addresses, and the instruction
+/// fails when they are not provided, so a new handle is never left undecryptable.
+pub fn call_allow_from_remaining<'info>(
+    inco_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    handle: Euint128,
+    allowed_pubkey: Pubkey,
+    account_offset: usize,
+) -> Result<()> {
+    require!(remaining_accounts.len() >= account_offset + 2, CustomError::MissingAllowanceAccounts);
+
+    let allowance_account = &remaining_accounts[account_offset];
+    let allowed_address = &remaining_accounts[account_offset + 1];
+
+    require!(allowed_address.key() == allowed_pubkey, CustomError::InvalidAllowanceAccount);
+    require!(
+        allowance_account.key() == allowance_address(handle, &allowed_pubkey),
+        CustomError::InvalidAllowanceAccount
+    );
+
+    let cpi_ctx = CpiContext::new(
+        inco_program.clone(),
+        Allow {
+            allowance_account: allowance_account.clone(),
+            signer: signer.clone(),
+            allowed_address: allowed_address.clone(),
+            system_program: system_program.clone(),
+        }
+    );
+
+    allow(cpi_ctx, handle.0, true, allowed_pubkey)?;
+    Ok(())
+}
+
+/// Grant `allowed` access to `handle` with its allowance account looked up in
+/// remaining_accounts by its derived address rather than by position. Missing
+/// accounts fail the instruction.
+pub fn call_allow_by_address<'info>(
+    inco_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    handle: Euint128,
+    allowed: &AccountInfo<'info>,
+) -> Result<()> {
+    let expected_allowance = allowance_address(handle, &allowed.key());
+    let allowance_account = remaining_accounts
+        .iter()
+        .find(|account| account.key() == expected_allowance && account.is_writable)
+        .ok_or(CustomError::MissingAllowanceAccounts)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_program.clone(),
+        Allow {
+            allowance_account: allowance_account.clone(),
+            signer: signer.clone(),
+            allowed_address: allowed.clone(),
+            system_program: system_program.clone(),
+        }
+    );
+    allow(cpi_ctx, handle.0, true, allowed.key())?;
+    Ok(())
+}
+
+/// Grant the mint auditor, if one is set, access to each of `handles`
+/// The auditor address and its allowance accounts are looked up in
+/// remaining_accounts by their derived addresses rather than by position,
+/// so a caller cannot substitute them. Missing accounts fail the instruction.
+pub fn allow_auditor<'info>(
+    inco_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    mint: &IncoMint,
+    handles: &[Euint128],
+) -> Result<()> {
+    let auditor = match mint.auditor {
+        COption::Some(auditor) => auditor,
+        COption::None => return Ok(()),
+    };
+
+    let auditor_address = remaining_accounts
+        .iter()
+        .find(|account| account.key() == auditor)
+        .ok_or(CustomError::MissingAuditorAllowance)?;
+
+    for handle in handles {
+        let expected_allowance = allowance_address(*handle, &auditor);
+        let allowance_account = remaining_accounts
+            .iter()
+            .find(|account| account.key() == expected_allowance && account.is_writable)
+            .ok_or(CustomError::MissingAuditorAllowance)?;
+
+        let cpi_ctx = CpiContext::new(
+            inco_program.clone(),
+            Allow {
+                allowance_account: allowance_account.clone(),
+                signer: signer.clone(),
+                allowed_address: auditor_address.clone(),
+                system_program: system_program.clone(),
+            }
+        );
+        allow(cpi_ctx, handle.0, true, auditor)?;
+    }
+
+    Ok(())
+}
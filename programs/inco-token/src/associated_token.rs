@@ -3,6 +3,9 @@ use inco_lightning::cpi::accounts::Operation;
 use inco_lightning::cpi::as_euint128;
 use inco_lightning::ID as INCO_LIGHTNING_ID;
 pub use crate::{ IncoAccount, IncoMint, COption, AccountState };
+use crate::token::native_reserve;
+use crate::token_2022::{default_account_state, init_account_extensions};
+use crate::events::InitializeAccountEvent;
 
 /// Create an associated token account for encrypted tokens
 pub fn create(ctx: Context<Create>) -> Result<()> {
@@ -25,16 +28,31 @@ pub fn create(ctx: Context<Create>) -> Result<()> {
 
     account.amount = zero_amount;
     account.delegate = COption::None;
-    account.state = AccountState::Initialized;
-    account.is_native = COption::None;
+    account.state = default_account_state(&mint.to_account_info())?;
+    account.is_native = native_reserve(&mint.key(), &account.to_account_info())?;
 
     // Create encrypted zero handle for delegated_amount
-    let cpi_ctx2 = CpiContext::new(inco, Operation { signer });
+    let cpi_ctx2 = CpiContext::new(inco, Operation { signer: signer.clone() });
     let zero_delegated = as_euint128(cpi_ctx2, 0)?;
 
     account.delegated_amount = zero_delegated;
     account.close_authority = COption::None;
 
+    init_account_extensions(
+        &account.to_account_info(),
+        &mint.to_account_info(),
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        zero_delegated,
+    )?;
+
+    emit!(InitializeAccountEvent {
+        account: account.key(),
+        mint: account.mint,
+        owner: account.owner,
+        amount: account.amount,
+    });
+
     Ok(())
 }
 
@@ -45,8 +63,8 @@ pub fn create_idempotent(ctx: Context<CreateIdempotent>) -> Result<()> {
 
     require!(mint.is_initialized, IncoAssociatedTokenError::UninitializedMint);
 
-    // If already initialized, just return successfully
-    if account.state == AccountState::Initialized {
+    // If already initialized (possibly frozen by default), just return successfully
+    if account.state != AccountState::Uninitialized {
         return Ok(());
     }
 
@@ -62,15 +80,30 @@ pub fn create_idempotent(ctx: Context<CreateIdempotent>) -> Result<()> {
 
     account.amount = zero_amount;
     account.delegate = COption::None;
-    account.state = AccountState::Initialized;
-    account.is_native = COption::None;
+    account.state = default_account_state(&mint.to_account_info())?;
+    account.is_native = native_reserve(&mint.key(), &account.to_account_info())?;
 
-    let cpi_ctx2 = CpiContext::new(inco, Operation { signer });
+    let cpi_ctx2 = CpiContext::new(inco, Operation { signer: signer.clone() });
     let zero_delegated = as_euint128(cpi_ctx2, 0)?;
 
     account.delegated_amount = zero_delegated;
     account.close_authority = COption::None;
 
+    init_account_extensions(
+        &account.to_account_info(),
+        &mint.to_account_info(),
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        zero_delegated,
+    )?;
+
+    emit!(InitializeAccountEvent {
+        account: account.key(),
+        mint: account.mint,
+        owner: account.owner,
+        amount: account.amount,
+    });
+
     Ok(())
 }
 
@@ -0,0 +1,1475 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::{system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use inco_lightning::types::Euint128;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::{accounts, instruction};
+use crate::extension::{get_extension, Extension, ExtensionType, ACCOUNT_EXTENSIONS_OFFSET, MINT_EXTENSIONS_OFFSET};
+use crate::token_2022::{extra_account_metas_address, ExtraAccountMeta, TRANSFER_HOOK_EXECUTE_DISCRIMINATOR};
+use crate::{
+    AccountState, AmountInput, AuthorityType, Collection, CreateMasterEditionArgs, CreateMetadataArgs, Edition,
+    IncoAccount, IncoEscrow, IncoMint, IncoMultisig, IncoVesting, MasterEdition, Metadata, PrintEditionArgs,
+    UpdateMetadataArgs, VestingSchedule, ESCROW_SEED, ESCROW_VAULT_SEED, MEMO_CHUNK_LEN, NATIVE_MINT_SEED, VESTING_SEED,
+    VESTING_VAULT_SEED,
+};
+pub use crate::allowance::allowance_address;
+pub use crate::associated_token::{get_associated_token_address, get_associated_token_address_with_program_id};
+
+// ========== AUTHORITIES AND ALLOWANCES ==========
+
+/// Authority of an instruction: a single signing key, or an IncoMultisig
+/// together with the members that sign for it
+/// The IDL does not mark authorities as signers, since they may be a multisig;
+/// the builders below mark a `Single` authority as signing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Authority {
+    Single(Pubkey),
+    Multisig { multisig: Pubkey, signers: Vec<Pubkey> },
+}
+
+impl Authority {
+    pub fn key(&self) -> Pubkey {
+        match self {
+            Authority::Single(key) => *key,
+            Authority::Multisig { multisig, .. } => *multisig,
+        }
+    }
+}
+
+impl From<Pubkey> for Authority {
+    fn from(key: Pubkey) -> Self {
+        Authority::Single(key)
+    }
+}
+
+/// Lets `allowed` decrypt `handle`
+/// New handles are only known once the instruction has run, so clients usually
+/// simulate it first and read the handles from the returned account data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AllowanceGrant {
+    pub handle: u128,
+    pub allowed: Pubkey,
+}
+
+impl AllowanceGrant {
+    pub fn new(handle: u128, allowed: Pubkey) -> Self {
+        Self { handle, allowed }
+    }
+
+    /// [allowance_account (mut), allowed_address (readonly)]
+    pub fn account_metas(&self) -> [AccountMeta; 2] {
+        [
+            AccountMeta::new(allowance_address(Euint128(self.handle), &self.allowed), false),
+            AccountMeta::new_readonly(self.allowed, false),
+        ]
+    }
+}
+
+/// remaining_accounts for a list of grants, in the order the instruction expects them
+pub fn allowance_account_metas(grants: &[AllowanceGrant]) -> Vec<AccountMeta> {
+    grants.iter().flat_map(|grant| grant.account_metas()).collect()
+}
+
+/// Trailing accounts of transfer and transfer_checked for a mint with a transfer hook:
+/// [hook_program, extra_account_metas, extra_accounts...]. Append them last, after the
+/// grants and any transfer_memo_account_meta.
+pub fn transfer_hook_account_metas(mint: &Pubkey, program_id: &Pubkey, extra_accounts: &[AccountMeta]) -> Vec<AccountMeta> {
+    let mut metas = vec![
+        AccountMeta::new_readonly(*program_id, false),
+        AccountMeta::new_readonly(extra_account_metas_address(mint, program_id), false),
+    ];
+    metas.extend_from_slice(extra_accounts);
+    metas
+}
+
+/// Data of an extra-account-metas PDA listing `extra_account_metas`, for hook
+/// programs to store at extra_account_metas_address
+pub fn extra_account_metas_data(extra_account_metas: &[ExtraAccountMeta]) -> Vec<u8> {
+    let length = 4 + extra_account_metas.len() * ExtraAccountMeta::LEN;
+    let mut data = TRANSFER_HOOK_EXECUTE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&(length as u32).to_le_bytes());
+    data.extend_from_slice(&(extra_account_metas.len() as u32).to_le_bytes());
+    for extra_account_meta in extra_account_metas {
+        data.extend_from_slice(&extra_account_meta.try_to_vec().unwrap());
+    }
+    data
+}
+
+/// Trailing account of transfer and transfer_checked into an account that requires
+/// memos; the transfer must directly follow a build_memo. Append it after the
+/// grants and before any transfer_hook_account_metas.
+pub fn transfer_memo_account_meta() -> AccountMeta {
+    AccountMeta::new_readonly(sysvar::instructions::ID, false)
+}
+
+fn build(
+    accounts: impl ToAccountMetas,
+    data: impl InstructionData,
+    authority: Option<&Authority>,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    match authority {
+        Some(Authority::Single(key)) => {
+            for meta in metas.iter_mut().filter(|meta| meta.pubkey == *key) {
+                meta.is_signer = true;
+            }
+        }
+        // Multisig members lead the remaining accounts; the first pays for allowance accounts
+        Some(Authority::Multisig { signers, .. }) => {
+            metas.extend(signers.iter().map(|signer| AccountMeta::new(*signer, true)));
+        }
+        None => {}
+    }
+    metas.extend(remaining_accounts);
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: metas,
+        data: data.data(),
+    }
+}
+
+// ========== PDA HELPERS ==========
+
+/// Address of the native (wrapped SOL) mint
+pub fn native_mint_address() -> Pubkey {
+    Pubkey::find_program_address(&[NATIVE_MINT_SEED], &crate::ID).0
+}
+
+/// Address of the escrow `maker` opens with `seed`
+pub fn escrow_address(maker: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(&[ESCROW_SEED, maker.as_ref(), &seed.to_le_bytes()], &crate::ID).0
+}
+
+/// Address of the vault holding an escrow's mint A tokens
+pub fn escrow_vault_address(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ESCROW_VAULT_SEED, escrow.as_ref()], &crate::ID).0
+}
+
+/// Address of the vesting `grantor` creates with `seed`
+pub fn vesting_address(grantor: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(&[VESTING_SEED, grantor.as_ref(), &seed.to_le_bytes()], &crate::ID).0
+}
+
+/// Address of the vault holding a vesting grant
+pub fn vesting_vault_address(vesting: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VESTING_VAULT_SEED, vesting.as_ref()], &crate::ID).0
+}
+
+// ========== ACCOUNT DECODERS ==========
+
+pub fn decode_mint(data: &[u8]) -> Result<IncoMint> {
+    IncoMint::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_account(data: &[u8]) -> Result<IncoAccount> {
+    IncoAccount::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_multisig(data: &[u8]) -> Result<IncoMultisig> {
+    IncoMultisig::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_metadata(data: &[u8]) -> Result<Metadata> {
+    Metadata::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_master_edition(data: &[u8]) -> Result<MasterEdition> {
+    MasterEdition::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_edition(data: &[u8]) -> Result<Edition> {
+    Edition::try_deserialize(&mut &data[..])
+}
+
+/// Extension `E` of a mint's account data, or None when it is not initialized
+pub fn decode_mint_extension<E: Extension>(data: &[u8]) -> Result<Option<E>> {
+    get_extension::<E>(data, MINT_EXTENSIONS_OFFSET)
+}
+
+/// Extension `E` of a token account's data, or None when it is not initialized
+pub fn decode_account_extension<E: Extension>(data: &[u8]) -> Result<Option<E>> {
+    get_extension::<E>(data, ACCOUNT_EXTENSIONS_OFFSET)
+}
+
+pub fn decode_escrow(data: &[u8]) -> Result<IncoEscrow> {
+    IncoEscrow::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_vesting(data: &[u8]) -> Result<IncoVesting> {
+    IncoVesting::try_deserialize(&mut &data[..])
+}
+
+// ========== TOKEN INSTRUCTIONS ==========
+
+pub fn initialize_mint(
+    mint: Pubkey,
+    payer: Pubkey,
+    decimals: u8,
+    mint_authority: Pubkey,
+    freeze_authority: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::InitializeMint {
+            mint,
+            payer,
+            system_program: system_program::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::InitializeMint { decimals, mint_authority, freeze_authority },
+        None,
+        vec![],
+    )
+}
+
+pub fn initialize_account(account: Pubkey, mint: Pubkey, owner: Pubkey, payer: Pubkey) -> Instruction {
+    build(
+        accounts::InitializeAccount {
+            account,
+            mint,
+            owner,
+            payer,
+            system_program: system_program::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::InitializeAccount {},
+        None,
+        vec![],
+    )
+}
+
+pub fn initialize_multisig(multisig: Pubkey, payer: Pubkey, m: u8, signers: &[Pubkey]) -> Instruction {
+    build(
+        accounts::InitializeMultisig {
+            multisig,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeMultisig { m },
+        None,
+        signers.iter().map(|signer| AccountMeta::new_readonly(*signer, false)).collect(),
+    )
+}
+
+pub fn initialize_native_mint(payer: Pubkey) -> Instruction {
+    build(
+        accounts::InitializeNativeMint {
+            mint: native_mint_address(),
+            payer,
+            system_program: system_program::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::InitializeNativeMint {},
+        None,
+        vec![],
+    )
+}
+
+pub fn sync_native(account: Pubkey, payer: Pubkey, grants: &[AllowanceGrant]) -> Instruction {
+    build(
+        accounts::SyncNative {
+            account,
+            mint: native_mint_address(),
+            payer,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::SyncNative {},
+        None,
+        allowance_account_metas(grants),
+    )
+}
+
+/// Must follow the Ed25519 attestation instruction for `account.amount`
+pub fn unwrap_native(
+    account: Pubkey,
+    destination: Pubkey,
+    owner: &Authority,
+    balance_plaintext: u128,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::UnwrapNative {
+            account,
+            mint: native_mint_address(),
+            destination,
+            owner: owner.key(),
+            instructions: sysvar::instructions::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::UnwrapNative { balance_plaintext: balance_plaintext.to_le_bytes().to_vec() },
+        Some(owner),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn mint_to(
+    mint: Pubkey,
+    account: Pubkey,
+    mint_authority: &Authority,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::IncoMintTo {
+            mint,
+            account,
+            mint_authority: mint_authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::MintTo { ciphertext, input_type },
+        Some(mint_authority),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn transfer(
+    source: Pubkey,
+    destination: Pubkey,
+    mint: Pubkey,
+    authority: &Authority,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::IncoTransfer {
+            source,
+            destination,
+            mint,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::Transfer { ciphertext, input_type },
+        Some(authority),
+        allowance_account_metas(grants),
+    )
+}
+
+/// `legs` pairs each destination with its ciphertext
+pub fn transfer_batch(
+    source: Pubkey,
+    mint: Pubkey,
+    authority: &Authority,
+    legs: Vec<(Pubkey, Vec<u8>)>,
+    input_type: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    let mut remaining_accounts: Vec<AccountMeta> = legs
+        .iter()
+        .map(|(destination, _)| AccountMeta::new(*destination, false))
+        .collect();
+    remaining_accounts.extend(allowance_account_metas(grants));
+
+    build(
+        accounts::IncoTransferBatch {
+            source,
+            mint,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::TransferBatch {
+            ciphertexts: legs.into_iter().map(|(_, ciphertext)| ciphertext).collect(),
+            input_type,
+        },
+        Some(authority),
+        remaining_accounts,
+    )
+}
+
+pub fn approve(
+    source: Pubkey,
+    mint: Pubkey,
+    delegate: Pubkey,
+    owner: &Authority,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::IncoApprove {
+            source,
+            mint,
+            delegate,
+            owner: owner.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::Approve { ciphertext, input_type },
+        Some(owner),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn revoke(source: Pubkey, owner: &Authority) -> Instruction {
+    build(
+        accounts::IncoRevoke {
+            source,
+            owner: owner.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::Revoke {},
+        Some(owner),
+        vec![],
+    )
+}
+
+pub fn burn(
+    account: Pubkey,
+    mint: Pubkey,
+    authority: &Authority,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::IncoBurn {
+            account,
+            mint,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::Burn { ciphertext, input_type },
+        Some(authority),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn freeze_account(account: Pubkey, mint: Pubkey, freeze_authority: &Authority) -> Instruction {
+    build(
+        accounts::FreezeAccount {
+            account,
+            mint,
+            freeze_authority: freeze_authority.key(),
+        },
+        instruction::FreezeAccount {},
+        Some(freeze_authority),
+        vec![],
+    )
+}
+
+pub fn thaw_account(account: Pubkey, mint: Pubkey, freeze_authority: &Authority) -> Instruction {
+    build(
+        accounts::ThawAccount {
+            account,
+            mint,
+            freeze_authority: freeze_authority.key(),
+        },
+        instruction::ThawAccount {},
+        Some(freeze_authority),
+        vec![],
+    )
+}
+
+/// Must follow the Ed25519 attestation instruction for `account.amount`
+pub fn close_account(
+    account: Pubkey,
+    destination: Pubkey,
+    authority: &Authority,
+    balance_plaintext: u128,
+) -> Instruction {
+    build(
+        accounts::CloseAccount {
+            account,
+            destination,
+            authority: authority.key(),
+            instructions: sysvar::instructions::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::CloseAccount { balance_plaintext: balance_plaintext.to_le_bytes().to_vec() },
+        Some(authority),
+        vec![],
+    )
+}
+
+pub fn set_mint_authority(mint: Pubkey, current_authority: &Authority, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::SetMintAuthority { mint, current_authority: current_authority.key() },
+        instruction::SetMintAuthority { new_authority },
+        Some(current_authority),
+        vec![],
+    )
+}
+
+pub fn set_freeze_authority(mint: Pubkey, current_authority: &Authority, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::SetFreezeAuthority { mint, current_authority: current_authority.key() },
+        instruction::SetFreezeAuthority { new_authority },
+        Some(current_authority),
+        vec![],
+    )
+}
+
+pub fn set_account_owner(account: Pubkey, current_owner: &Authority, new_owner: Pubkey) -> Instruction {
+    build(
+        accounts::SetAccountOwner {
+            account,
+            current_owner: current_owner.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::SetAccountOwner { new_owner },
+        Some(current_owner),
+        vec![],
+    )
+}
+
+pub fn set_close_authority(account: Pubkey, owner: &Authority, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::SetCloseAuthority { account, owner: owner.key() },
+        instruction::SetCloseAuthority { new_authority },
+        Some(owner),
+        vec![],
+    )
+}
+
+pub fn set_auditor(mint: Pubkey, mint_authority: &Authority, auditor: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::SetAuditor { mint, mint_authority: mint_authority.key() },
+        instruction::SetAuditor { auditor },
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn migrate_mint(mint: Pubkey, payer: Pubkey) -> Instruction {
+    build(
+        accounts::MigrateMint { mint, payer, system_program: system_program::ID },
+        instruction::MigrateMint {},
+        None,
+        vec![],
+    )
+}
+
+pub fn set_max_supply(
+    mint: Pubkey,
+    mint_authority: &Authority,
+    max_supply: AmountInput,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::SetMaxSupply {
+            mint,
+            mint_authority: mint_authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::SetMaxSupply { max_supply },
+        Some(mint_authority),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn set_authority(
+    owned: Pubkey,
+    current_authority: &Authority,
+    authority_type: AuthorityType,
+    new_authority: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::SetAuthority {
+            owned,
+            current_authority: current_authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::SetAuthority { authority_type, new_authority },
+        Some(current_authority),
+        vec![],
+    )
+}
+
+// ========== ESCROW INSTRUCTIONS ==========
+
+#[allow(clippy::too_many_arguments)]
+pub fn make_escrow(
+    maker_source: Pubkey,
+    maker_receive: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    maker: &Authority,
+    payer: Pubkey,
+    seed: u64,
+    offered_ciphertext: Vec<u8>,
+    requested_ciphertext: Vec<u8>,
+    input_type: u8,
+    taker: Option<Pubkey>,
+    expiry: Option<i64>,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    let escrow = escrow_address(&maker.key(), seed);
+    build(
+        accounts::MakeEscrow {
+            escrow,
+            vault: escrow_vault_address(&escrow),
+            maker_source,
+            maker_receive,
+            mint_a,
+            mint_b,
+            maker: maker.key(),
+            payer,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::MakeEscrow {
+            seed,
+            offered_ciphertext,
+            requested_ciphertext,
+            input_type,
+            taker,
+            expiry,
+        },
+        Some(maker),
+        allowance_account_metas(grants),
+    )
+}
+
+/// `state` is the decoded escrow, which names the vault, mints and maker accounts.
+/// The expected ciphertexts encrypt the offered and requested amounts the taker agreed to
+#[allow(clippy::too_many_arguments)]
+pub fn fill_escrow(
+    escrow: Pubkey,
+    state: &IncoEscrow,
+    taker_source: Pubkey,
+    taker_receive: Pubkey,
+    taker: &Authority,
+    expected_offered_ciphertext: Vec<u8>,
+    expected_requested_ciphertext: Vec<u8>,
+    input_type: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::FillEscrow {
+            escrow,
+            vault: state.vault,
+            maker_receive: state.maker_receive,
+            taker_source,
+            taker_receive,
+            mint_a: state.mint_a,
+            mint_b: state.mint_b,
+            taker: taker.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::FillEscrow {
+            expected_offered_ciphertext,
+            expected_requested_ciphertext,
+            input_type,
+        },
+        Some(taker),
+        allowance_account_metas(grants),
+    )
+}
+
+/// `authority` is the maker before expiry and any signer after it
+pub fn cancel_escrow(
+    escrow: Pubkey,
+    state: &IncoEscrow,
+    authority: &Authority,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::CancelEscrow {
+            escrow,
+            vault: state.vault,
+            maker_refund: state.maker_refund,
+            mint_a: state.mint_a,
+            maker: state.maker,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::CancelEscrow {},
+        Some(authority),
+        allowance_account_metas(grants),
+    )
+}
+
+// ========== VESTING INSTRUCTIONS ==========
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_vesting(
+    source: Pubkey,
+    mint: Pubkey,
+    beneficiary: Pubkey,
+    grantor: &Authority,
+    payer: Pubkey,
+    seed: u64,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    schedule: VestingSchedule,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    let vesting = vesting_address(&grantor.key(), seed);
+    build(
+        accounts::CreateVesting {
+            vesting,
+            vault: vesting_vault_address(&vesting),
+            source,
+            mint,
+            beneficiary,
+            grantor: grantor.key(),
+            payer,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateVesting { seed, ciphertext, input_type, schedule },
+        Some(grantor),
+        allowance_account_metas(grants),
+    )
+}
+
+/// `state` is the decoded vesting, which names the vault, mint and grantor
+pub fn claim(
+    vesting: Pubkey,
+    state: &IncoVesting,
+    destination: Pubkey,
+    beneficiary: &Authority,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::Claim {
+            vesting,
+            vault: state.vault,
+            destination,
+            mint: state.mint,
+            beneficiary: beneficiary.key(),
+            grantor: state.grantor,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::Claim {},
+        Some(beneficiary),
+        allowance_account_metas(grants),
+    )
+}
+
+// ========== MEMO INSTRUCTIONS ==========
+
+pub fn build_memo(
+    authority: Pubkey,
+    encrypted_memo: Vec<u8>,
+    input_type: u8,
+    additional_signers: &[Pubkey],
+) -> Instruction {
+    build(
+        accounts::BuildMemo {
+            authority,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::BuildMemo { encrypted_memo, input_type },
+        None,
+        additional_signers.iter().map(|signer| AccountMeta::new_readonly(*signer, true)).collect(),
+    )
+}
+
+/// Each grant names a recipient of the memo; its handle is the memo handle
+pub fn build_memo_with_recipients(
+    authority: Pubkey,
+    encrypted_memo: Vec<u8>,
+    input_type: u8,
+    additional_signers: &[Pubkey],
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    let mut remaining_accounts: Vec<AccountMeta> =
+        additional_signers.iter().map(|signer| AccountMeta::new_readonly(*signer, true)).collect();
+    remaining_accounts.extend(
+        grants
+            .iter()
+            .filter(|grant| !additional_signers.contains(&grant.allowed))
+            .map(|grant| AccountMeta::new_readonly(grant.allowed, false)),
+    );
+    remaining_accounts.extend(
+        grants
+            .iter()
+            .map(|grant| AccountMeta::new(allowance_address(Euint128(grant.handle), &grant.allowed), false)),
+    );
+
+    build(
+        accounts::BuildMemoWithRecipients {
+            authority,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::BuildMemoWithRecipients {
+            encrypted_memo,
+            input_type,
+            recipients: grants.iter().map(|grant| grant.allowed).collect(),
+        },
+        None,
+        remaining_accounts,
+    )
+}
+
+/// Plaintext chunks of `memo` as little-endian u128 values, ready to be encrypted
+/// one by one for build_long_memo; the last chunk is zero padded
+pub fn memo_chunks(memo: &[u8]) -> Vec<u128> {
+    memo.chunks(MEMO_CHUNK_LEN)
+        .map(|chunk| {
+            let mut bytes = [0u8; MEMO_CHUNK_LEN];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            u128::from_le_bytes(bytes)
+        })
+        .collect()
+}
+
+/// `grants` covers each chunk handle for the authority, every additional signer and the recipient
+pub fn build_long_memo(
+    authority: Pubkey,
+    encrypted_chunks: Vec<Vec<u8>>,
+    input_type: u8,
+    additional_signers: &[Pubkey],
+    recipient: Option<Pubkey>,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    let mut remaining_accounts: Vec<AccountMeta> =
+        additional_signers.iter().map(|signer| AccountMeta::new_readonly(*signer, true)).collect();
+    if let Some(recipient) = recipient.filter(|recipient| !additional_signers.contains(recipient)) {
+        remaining_accounts.push(AccountMeta::new_readonly(recipient, false));
+    }
+    remaining_accounts.extend(
+        grants
+            .iter()
+            .map(|grant| AccountMeta::new(allowance_address(Euint128(grant.handle), &grant.allowed), false)),
+    );
+
+    build(
+        accounts::BuildLongMemo {
+            authority,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::BuildLongMemo { encrypted_chunks, input_type, recipient },
+        None,
+        remaining_accounts,
+    )
+}
+
+// ========== ASSOCIATED TOKEN INSTRUCTIONS ==========
+
+pub fn create(payer: Pubkey, wallet: Pubkey, mint: Pubkey) -> Instruction {
+    build(
+        accounts::Create {
+            payer,
+            associated_token: get_associated_token_address(&wallet, &mint),
+            wallet,
+            mint,
+            system_program: system_program::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::Create {},
+        None,
+        vec![],
+    )
+}
+
+pub fn create_idempotent(payer: Pubkey, wallet: Pubkey, mint: Pubkey) -> Instruction {
+    build(
+        accounts::CreateIdempotent {
+            payer,
+            associated_token: get_associated_token_address(&wallet, &mint),
+            wallet,
+            mint,
+            system_program: system_program::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::CreateIdempotent {},
+        None,
+        vec![],
+    )
+}
+
+// ========== METADATA INSTRUCTIONS ==========
+
+pub fn create_metadata_account(
+    metadata: Pubkey,
+    mint: Pubkey,
+    mint_authority: Pubkey,
+    payer: Pubkey,
+    update_authority: Pubkey,
+    args: CreateMetadataArgs,
+) -> Instruction {
+    build(
+        accounts::CreateMetadata {
+            metadata,
+            mint,
+            mint_authority,
+            payer,
+            update_authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateMetadataAccount { args },
+        None,
+        vec![],
+    )
+}
+
+pub fn update_metadata_account(metadata: Pubkey, update_authority: Pubkey, args: UpdateMetadataArgs) -> Instruction {
+    build(
+        accounts::UpdateMetadata { metadata, update_authority },
+        instruction::UpdateMetadataAccount { args },
+        None,
+        vec![],
+    )
+}
+
+pub fn create_master_edition(
+    edition: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+    mint_authority: Pubkey,
+    payer: Pubkey,
+    update_authority: Pubkey,
+    args: CreateMasterEditionArgs,
+) -> Instruction {
+    build(
+        accounts::CreateMasterEdition {
+            edition,
+            metadata,
+            mint,
+            mint_authority,
+            payer,
+            update_authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateMasterEdition { args },
+        None,
+        vec![],
+    )
+}
+
+pub fn print_edition(edition: Pubkey, master_edition: Pubkey, payer: Pubkey, args: PrintEditionArgs) -> Instruction {
+    build(
+        accounts::PrintEdition {
+            edition,
+            master_edition,
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::PrintEdition { args },
+        None,
+        vec![],
+    )
+}
+
+pub fn sign_metadata(metadata: Pubkey, creator: Pubkey) -> Instruction {
+    build(
+        accounts::SignMetadata { metadata, creator },
+        instruction::SignMetadata {},
+        None,
+        vec![],
+    )
+}
+
+pub fn remove_creator_verification(metadata: Pubkey, creator: Pubkey) -> Instruction {
+    build(
+        accounts::RemoveCreatorVerification { metadata, creator },
+        instruction::RemoveCreatorVerification {},
+        None,
+        vec![],
+    )
+}
+
+pub fn set_and_verify_collection(metadata: Pubkey, update_authority: Pubkey, collection: Collection) -> Instruction {
+    build(
+        accounts::SetAndVerifyCollection { metadata, update_authority },
+        instruction::SetAndVerifyCollection { collection },
+        None,
+        vec![],
+    )
+}
+
+pub fn verify_collection(metadata: Pubkey, collection_authority: Pubkey) -> Instruction {
+    build(
+        accounts::VerifyCollection { metadata, collection_authority },
+        instruction::VerifyCollection {},
+        None,
+        vec![],
+    )
+}
+
+pub fn unverify_collection(metadata: Pubkey, collection_authority: Pubkey) -> Instruction {
+    build(
+        accounts::UnverifyCollection { metadata, collection_authority },
+        instruction::UnverifyCollection {},
+        None,
+        vec![],
+    )
+}
+
+// ========== TOKEN 2022 INSTRUCTIONS ==========
+
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked(
+    source: Pubkey,
+    mint: Pubkey,
+    destination: Pubkey,
+    authority: &Authority,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    decimals: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::TransferChecked {
+            source,
+            mint,
+            destination,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::TransferChecked { ciphertext, input_type, decimals },
+        Some(authority),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn mint_to_checked(
+    mint: Pubkey,
+    account: Pubkey,
+    authority: &Authority,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    decimals: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::MintToChecked {
+            mint,
+            account,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::MintToChecked { ciphertext, input_type, decimals },
+        Some(authority),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn burn_checked(
+    account: Pubkey,
+    mint: Pubkey,
+    authority: &Authority,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    decimals: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::BurnChecked {
+            account,
+            mint,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::BurnChecked { ciphertext, input_type, decimals },
+        Some(authority),
+        allowance_account_metas(grants),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn approve_checked(
+    source: Pubkey,
+    mint: Pubkey,
+    delegate: Pubkey,
+    owner: &Authority,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    decimals: u8,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::ApproveChecked {
+            source,
+            mint,
+            delegate,
+            owner: owner.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::ApproveChecked { ciphertext, input_type, decimals },
+        Some(owner),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn initialize_account3(account: Pubkey, mint: Pubkey, authority: Pubkey) -> Instruction {
+    build(
+        accounts::InitializeAccount3 {
+            account,
+            mint,
+            authority,
+            system_program: system_program::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::InitializeAccount3 {},
+        None,
+        vec![],
+    )
+}
+
+pub fn revoke_2022(source: Pubkey, authority: &Authority) -> Instruction {
+    build(
+        accounts::Revoke2022 {
+            source,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::Revoke2022 {},
+        Some(authority),
+        vec![],
+    )
+}
+
+/// Must follow the Ed25519 attestation instruction for `account.amount`
+pub fn close_account_2022(
+    account: Pubkey,
+    destination: Pubkey,
+    authority: &Authority,
+    balance_plaintext: u128,
+) -> Instruction {
+    build(
+        accounts::CloseAccount2022 {
+            account,
+            destination,
+            authority: authority.key(),
+            instructions: sysvar::instructions::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::CloseAccount2022 { balance_plaintext: balance_plaintext.to_le_bytes().to_vec() },
+        Some(authority),
+        vec![],
+    )
+}
+
+pub fn reallocate(account: Pubkey, owner: &Authority, payer: Pubkey, extension_types: Vec<ExtensionType>) -> Instruction {
+    build(
+        accounts::Reallocate {
+            account,
+            owner: owner.key(),
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::Reallocate { extension_types },
+        Some(owner),
+        vec![],
+    )
+}
+
+pub fn reallocate_mint(
+    mint: Pubkey,
+    mint_authority: &Authority,
+    payer: Pubkey,
+    extension_types: Vec<ExtensionType>,
+) -> Instruction {
+    build(
+        accounts::ReallocateMint {
+            mint,
+            mint_authority: mint_authority.key(),
+            payer,
+            system_program: system_program::ID,
+        },
+        instruction::ReallocateMint { extension_types },
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn initialize_metadata_pointer(
+    mint: Pubkey,
+    mint_authority: &Authority,
+    authority: Option<Pubkey>,
+    metadata_address: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::InitializeMetadataPointer { mint, mint_authority: mint_authority.key() },
+        instruction::InitializeMetadataPointer { authority, metadata_address },
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn update_metadata_pointer(mint: Pubkey, authority: &Authority, metadata_address: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::UpdateMetadataPointer { mint, authority: authority.key() },
+        instruction::UpdateMetadataPointer { metadata_address },
+        Some(authority),
+        vec![],
+    )
+}
+
+pub fn initialize_transfer_hook(
+    mint: Pubkey,
+    mint_authority: &Authority,
+    authority: Option<Pubkey>,
+    program_id: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::InitializeTransferHook { mint, mint_authority: mint_authority.key() },
+        instruction::InitializeTransferHook { authority, program_id },
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn update_transfer_hook(mint: Pubkey, authority: &Authority, program_id: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::UpdateTransferHook { mint, authority: authority.key() },
+        instruction::UpdateTransferHook { program_id },
+        Some(authority),
+        vec![],
+    )
+}
+
+pub fn initialize_non_transferable_mint(mint: Pubkey, mint_authority: &Authority) -> Instruction {
+    build(
+        accounts::InitializeNonTransferableMint {
+            mint,
+            mint_authority: mint_authority.key(),
+            instructions: sysvar::instructions::ID,
+        },
+        instruction::InitializeNonTransferableMint {},
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn enable_required_transfer_memos(account: Pubkey, owner: &Authority) -> Instruction {
+    build(
+        accounts::RequiredTransferMemos { account, owner: owner.key() },
+        instruction::EnableRequiredTransferMemos {},
+        Some(owner),
+        vec![],
+    )
+}
+
+pub fn disable_required_transfer_memos(account: Pubkey, owner: &Authority) -> Instruction {
+    build(
+        accounts::RequiredTransferMemos { account, owner: owner.key() },
+        instruction::DisableRequiredTransferMemos {},
+        Some(owner),
+        vec![],
+    )
+}
+
+pub fn initialize_mint_close_authority(
+    mint: Pubkey,
+    mint_authority: &Authority,
+    close_authority: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::InitializeMintCloseAuthority {
+            mint,
+            mint_authority: mint_authority.key(),
+            instructions: sysvar::instructions::ID,
+        },
+        instruction::InitializeMintCloseAuthority { close_authority },
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn update_mint_close_authority(
+    mint: Pubkey,
+    close_authority: &Authority,
+    new_authority: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::UpdateMintCloseAuthority { mint, close_authority: close_authority.key() },
+        instruction::UpdateMintCloseAuthority { new_authority },
+        Some(close_authority),
+        vec![],
+    )
+}
+
+pub fn close_mint(mint: Pubkey, destination: Pubkey, authority: &Authority, supply_plaintext: u128) -> Instruction {
+    build(
+        accounts::CloseMint {
+            mint,
+            destination,
+            authority: authority.key(),
+            instructions: sysvar::instructions::ID,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+        },
+        instruction::CloseMint { supply_plaintext: supply_plaintext.to_le_bytes().to_vec() },
+        Some(authority),
+        vec![],
+    )
+}
+
+pub fn initialize_interest_bearing_mint(
+    mint: Pubkey,
+    mint_authority: &Authority,
+    rate_authority: Option<Pubkey>,
+    rate: i16,
+) -> Instruction {
+    build(
+        accounts::InitializeInterestBearingMint { mint, mint_authority: mint_authority.key() },
+        instruction::InitializeInterestBearingMint { rate_authority, rate },
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn update_interest_rate(mint: Pubkey, rate_authority: &Authority, rate: i16) -> Instruction {
+    build(
+        accounts::UpdateInterestRate { mint, rate_authority: rate_authority.key() },
+        instruction::UpdateInterestRate { rate },
+        Some(rate_authority),
+        vec![],
+    )
+}
+
+pub fn amount_to_ui_amount(account: Pubkey, mint: Pubkey, authority: Pubkey, grants: &[AllowanceGrant]) -> Instruction {
+    build(
+        accounts::AmountToUiAmount {
+            account,
+            mint,
+            authority,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::AmountToUiAmount {},
+        None,
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn initialize_permanent_delegate(mint: Pubkey, mint_authority: &Authority, delegate: Pubkey) -> Instruction {
+    build(
+        accounts::InitializePermanentDelegate {
+            mint,
+            mint_authority: mint_authority.key(),
+            instructions: sysvar::instructions::ID,
+        },
+        instruction::InitializePermanentDelegate { delegate },
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn update_permanent_delegate(mint: Pubkey, delegate: &Authority, new_delegate: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::UpdatePermanentDelegate { mint, delegate: delegate.key() },
+        instruction::UpdatePermanentDelegate { new_delegate },
+        Some(delegate),
+        vec![],
+    )
+}
+
+pub fn initialize_default_account_state(mint: Pubkey, mint_authority: &Authority, state: AccountState) -> Instruction {
+    build(
+        accounts::InitializeDefaultAccountState { mint, mint_authority: mint_authority.key() },
+        instruction::InitializeDefaultAccountState { state },
+        Some(mint_authority),
+        vec![],
+    )
+}
+
+pub fn update_default_account_state(mint: Pubkey, freeze_authority: &Authority, state: AccountState) -> Instruction {
+    build(
+        accounts::UpdateDefaultAccountState { mint, freeze_authority: freeze_authority.key() },
+        instruction::UpdateDefaultAccountState { state },
+        Some(freeze_authority),
+        vec![],
+    )
+}
+
+pub fn initialize_transfer_fee_config(
+    mint: Pubkey,
+    mint_authority: &Authority,
+    transfer_fee_config_authority: Option<Pubkey>,
+    withdraw_withheld_authority: Option<Pubkey>,
+    transfer_fee_basis_points: u16,
+    maximum_fee: AmountInput,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::InitializeTransferFeeConfig {
+            mint,
+            mint_authority: mint_authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+            instructions: sysvar::instructions::ID,
+        },
+        instruction::InitializeTransferFeeConfig {
+            transfer_fee_config_authority,
+            withdraw_withheld_authority,
+            transfer_fee_basis_points,
+            maximum_fee,
+        },
+        Some(mint_authority),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn set_transfer_fee(
+    mint: Pubkey,
+    authority: &Authority,
+    transfer_fee_basis_points: u16,
+    maximum_fee: AmountInput,
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    build(
+        accounts::SetTransferFee {
+            mint,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::SetTransferFee { transfer_fee_basis_points, maximum_fee },
+        Some(authority),
+        allowance_account_metas(grants),
+    )
+}
+
+pub fn harvest_withheld_to_mint(mint: Pubkey, authority: Pubkey, sources: &[Pubkey]) -> Instruction {
+    build(
+        accounts::HarvestWithheldToMint {
+            mint,
+            authority,
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::HarvestWithheldToMint {},
+        None,
+        sources.iter().map(|source| AccountMeta::new(*source, false)).collect(),
+    )
+}
+
+pub fn withdraw_withheld_tokens(
+    mint: Pubkey,
+    destination: Pubkey,
+    authority: &Authority,
+    sources: &[Pubkey],
+    grants: &[AllowanceGrant],
+) -> Instruction {
+    let mut remaining_accounts: Vec<AccountMeta> = sources
+        .iter()
+        .map(|source| AccountMeta::new(*source, false))
+        .collect();
+    remaining_accounts.extend(allowance_account_metas(grants));
+
+    build(
+        accounts::WithdrawWithheldTokens {
+            mint,
+            destination,
+            authority: authority.key(),
+            inco_lightning_program: INCO_LIGHTNING_ID,
+            system_program: system_program::ID,
+        },
+        instruction::WithdrawWithheldTokens { num_sources: sources.len() as u8 },
+        Some(authority),
+        remaining_accounts,
+    )
+}
@@ -0,0 +1,492 @@
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::{e_add, e_eq, e_ge, e_select, e_sub, new_euint128, as_euint128};
+use inco_lightning::types::Euint128;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::events::*;
+use crate::allowance::{allow_auditor, call_allow_from_remaining};
+use crate::token::{is_native_mint, operation_signer, validate_owner};
+use crate::token_2022::require_plain_transfers;
+use crate::{AccountState, COption, CustomError, IncoAccount, IncoMint};
+
+/// Seed prefix of escrow PDAs: [ESCROW_SEED, maker, seed]
+pub const ESCROW_SEED: &[u8] = b"escrow";
+/// Seed prefix of escrow vault PDAs: [ESCROW_VAULT_SEED, escrow]
+pub const ESCROW_VAULT_SEED: &[u8] = b"escrow_vault";
+
+// ========== ESCROW INSTRUCTIONS ==========
+
+/// Lock an encrypted amount of mint A in a vault owned by the escrow PDA, asking
+/// for an encrypted amount of mint B in return.
+/// The lock is all or nothing: when the maker's balance is too low the vault holds
+/// zero and no fill can succeed.
+/// remaining_accounts (after any multisig signers):
+///   [0] allowance_account (mut) - new maker source balance
+///   [1] maker_address (readonly)
+///   [2] allowance_account (mut) - vault balance
+///   [3] maker_address (readonly)
+///   [4] allowance_account (mut) - offered amount, only when a taker is set
+///   [5] taker_address (readonly)
+///   [6] allowance_account (mut) - requested amount, only when a taker is set
+///   [7] taker_address (readonly)
+pub fn make_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, MakeEscrow<'info>>,
+    seed: u64,
+    offered_ciphertext: Vec<u8>,
+    requested_ciphertext: Vec<u8>,
+    input_type: u8,
+    taker: Option<Pubkey>,
+    expiry: Option<i64>,
+) -> Result<()> {
+    let maker_source = &mut ctx.accounts.maker_source;
+    let vault = &mut ctx.accounts.vault;
+    let escrow = &mut ctx.accounts.escrow;
+
+    require!(maker_source.state != AccountState::Frozen, CustomError::AccountFrozen);
+    require!(
+        !is_native_mint(&ctx.accounts.mint_a.key()) && !is_native_mint(&ctx.accounts.mint_b.key()),
+        CustomError::NativeNotSupported
+    );
+    require_plain_transfers(&ctx.accounts.mint_a.to_account_info())?;
+    require_plain_transfers(&ctx.accounts.mint_b.to_account_info())?;
+    if let Some(expiry) = expiry {
+        require!(Clock::get()?.unix_timestamp < expiry, IncoEscrowError::EscrowExpired);
+    }
+
+    let maker = ctx.accounts.maker.key();
+    let multisig_signers = validate_owner(&maker, &ctx.accounts.maker, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.maker, ctx.remaining_accounts, multisig_signers);
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let offered = new_euint128(cpi_ctx, offered_ciphertext, input_type)?;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let requested = new_euint128(cpi_ctx2, requested_ciphertext, input_type)?;
+
+    let cpi_ctx3 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let has_sufficient = e_ge(cpi_ctx3, maker_source.amount, offered, 0u8)?;
+
+    let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero_value = as_euint128(cpi_ctx4, 0)?;
+
+    let cpi_ctx5 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let locked = e_select(cpi_ctx5, has_sufficient, offered, zero_value, 0u8)?;
+
+    let cpi_ctx6 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_source_balance = e_sub(cpi_ctx6, maker_source.amount, locked, 0u8)?;
+    maker_source.amount = new_source_balance;
+
+    vault.mint = ctx.accounts.mint_a.key();
+    vault.owner = escrow.key();
+    vault.amount = locked;
+    vault.delegate = COption::None;
+    vault.state = AccountState::Initialized;
+    vault.is_native = COption::None;
+    vault.delegated_amount = zero_value;
+    vault.close_authority = COption::None;
+
+    escrow.maker = maker;
+    escrow.seed = seed;
+    escrow.mint_a = ctx.accounts.mint_a.key();
+    escrow.mint_b = ctx.accounts.mint_b.key();
+    escrow.vault = vault.key();
+    escrow.maker_refund = maker_source.key();
+    escrow.maker_receive = ctx.accounts.maker_receive.key();
+    escrow.taker = match taker {
+        Some(taker) => COption::Some(taker),
+        None => COption::None,
+    };
+    escrow.offered = offered;
+    escrow.requested = requested;
+    escrow.expiry = match expiry {
+        Some(expiry) => COption::Some(expiry),
+        None => COption::None,
+    };
+    escrow.bump = ctx.bumps.escrow;
+
+    let system_program = ctx.accounts.system_program.to_account_info();
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, new_source_balance, maker, 0)?;
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, locked, maker, 2)?;
+    if let Some(taker) = taker {
+        call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, offered, taker, 4)?;
+        call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, requested, taker, 6)?;
+    }
+
+    allow_auditor(
+        &inco, &signer, &system_program, remaining_accounts,
+        &ctx.accounts.mint_a, &[offered, locked, new_source_balance],
+    )?;
+    allow_auditor(
+        &inco, &signer, &system_program, remaining_accounts,
+        &ctx.accounts.mint_b, &[requested],
+    )?;
+
+    emit!(EscrowCreatedEvent {
+        escrow: escrow.key(),
+        maker,
+        mint_a: escrow.mint_a,
+        mint_b: escrow.mint_b,
+        offered,
+        requested,
+        taker,
+        expiry,
+    });
+
+    Ok(())
+}
+
+/// Fill an escrow: the taker pays the requested amount of mint B to the maker and
+/// receives the vault's mint A. Both legs are selected on the same encrypted
+/// conditions, so either both move or neither does.
+/// The taker states the offered and requested amounts it agreed to; when either
+/// differs from the escrow's terms nothing moves, so an open escrow is never filled blind.
+/// A fill that moves nothing leaves the escrow open; the maker closes it with cancel_escrow.
+/// remaining_accounts (after any multisig signers):
+///   [0] allowance_account (mut) - new taker source balance
+///   [1] taker_source_owner (readonly)
+///   [2] allowance_account (mut) - new taker receive balance
+///   [3] taker_receive_owner (readonly)
+///   [4] allowance_account (mut) - new maker receive balance
+///   [5] maker_receive_owner (readonly)
+///   [6] allowance_account (mut) - new vault balance
+///   [7] maker_address (readonly)
+pub fn fill_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, FillEscrow<'info>>,
+    expected_offered_ciphertext: Vec<u8>,
+    expected_requested_ciphertext: Vec<u8>,
+    input_type: u8,
+) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    let vault = &mut ctx.accounts.vault;
+    let maker_receive = &mut ctx.accounts.maker_receive;
+    let taker_source = &mut ctx.accounts.taker_source;
+    let taker_receive = &mut ctx.accounts.taker_receive;
+
+    require!(vault.state != AccountState::Frozen, CustomError::AccountFrozen);
+    require!(maker_receive.state != AccountState::Frozen, CustomError::AccountFrozen);
+    require!(taker_source.state != AccountState::Frozen, CustomError::AccountFrozen);
+    require!(taker_receive.state != AccountState::Frozen, CustomError::AccountFrozen);
+    // The mints may have gained extensions since the escrow was made
+    require_plain_transfers(&ctx.accounts.mint_a.to_account_info())?;
+    require_plain_transfers(&ctx.accounts.mint_b.to_account_info())?;
+    if let COption::Some(expiry) = escrow.expiry {
+        require!(Clock::get()?.unix_timestamp < expiry, IncoEscrowError::EscrowExpired);
+    }
+
+    let taker = ctx.accounts.taker.key();
+    if let COption::Some(expected_taker) = escrow.taker {
+        require!(taker == expected_taker, IncoEscrowError::TakerMismatch);
+    }
+    require!(taker_source.owner == taker, CustomError::OwnerMismatch);
+    let multisig_signers = validate_owner(&taker, &ctx.accounts.taker, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.taker, ctx.remaining_accounts, multisig_signers);
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero_value = as_euint128(cpi_ctx, 0)?;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let expected_offered = new_euint128(cpi_ctx2, expected_offered_ciphertext, input_type)?;
+
+    let cpi_ctx3 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let expected_requested = new_euint128(cpi_ctx3, expected_requested_ciphertext, input_type)?;
+
+    let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let offered_matches = e_eq(cpi_ctx4, escrow.offered, expected_offered, 0u8)?;
+
+    let cpi_ctx5 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let requested_matches = e_eq(cpi_ctx5, escrow.requested, expected_requested, 0u8)?;
+
+    // The vault only holds the full offer when the maker's lock succeeded
+    let cpi_ctx6 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let is_funded = e_ge(cpi_ctx6, vault.amount, escrow.offered, 0u8)?;
+
+    // Both legs are zeroed unless the vault is funded and the taker's terms match
+    let cpi_ctx7 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let funded_due = e_select(cpi_ctx7, is_funded, escrow.requested, zero_value, 0u8)?;
+
+    let cpi_ctx8 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let offered_due = e_select(cpi_ctx8, offered_matches, funded_due, zero_value, 0u8)?;
+
+    let cpi_ctx9 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let due = e_select(cpi_ctx9, requested_matches, offered_due, zero_value, 0u8)?;
+
+    let cpi_ctx10 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let funded_release = e_select(cpi_ctx10, is_funded, vault.amount, zero_value, 0u8)?;
+
+    let cpi_ctx11 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let offered_release = e_select(cpi_ctx11, offered_matches, funded_release, zero_value, 0u8)?;
+
+    let cpi_ctx12 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let releasable = e_select(cpi_ctx12, requested_matches, offered_release, zero_value, 0u8)?;
+
+    let cpi_ctx13 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let can_pay = e_ge(cpi_ctx13, taker_source.amount, due, 0u8)?;
+
+    let cpi_ctx14 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let paid = e_select(cpi_ctx14, can_pay, due, zero_value, 0u8)?;
+
+    let cpi_ctx15 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let released = e_select(cpi_ctx15, can_pay, releasable, zero_value, 0u8)?;
+
+    let cpi_ctx16 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_taker_source_balance = e_sub(cpi_ctx16, taker_source.amount, paid, 0u8)?;
+    taker_source.amount = new_taker_source_balance;
+
+    let cpi_ctx17 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_maker_receive_balance = e_add(cpi_ctx17, maker_receive.amount, paid, 0u8)?;
+    maker_receive.amount = new_maker_receive_balance;
+
+    let cpi_ctx18 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_vault_balance = e_sub(cpi_ctx18, vault.amount, released, 0u8)?;
+    vault.amount = new_vault_balance;
+
+    let cpi_ctx19 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_taker_receive_balance = e_add(cpi_ctx19, taker_receive.amount, released, 0u8)?;
+    taker_receive.amount = new_taker_receive_balance;
+
+    let system_program = ctx.accounts.system_program.to_account_info();
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, new_taker_source_balance, taker_source.owner, 0)?;
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, new_taker_receive_balance, taker_receive.owner, 2)?;
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, new_maker_receive_balance, maker_receive.owner, 4)?;
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, new_vault_balance, escrow.maker, 6)?;
+
+    allow_auditor(
+        &inco, &signer, &system_program, remaining_accounts,
+        &ctx.accounts.mint_a, &[released, new_vault_balance, new_taker_receive_balance],
+    )?;
+    allow_auditor(
+        &inco, &signer, &system_program, remaining_accounts,
+        &ctx.accounts.mint_b, &[paid, new_taker_source_balance, new_maker_receive_balance],
+    )?;
+
+    emit!(EscrowFilledEvent {
+        escrow: escrow.key(),
+        taker,
+        paid,
+        released,
+    });
+
+    Ok(())
+}
+
+/// Return whatever the vault holds to the maker and close the escrow and vault.
+/// Before expiry only the maker can cancel; after expiry any signer can, and the
+/// funds and rent still go to the maker.
+/// remaining_accounts (after any multisig signers):
+///   [0] allowance_account (mut) - new maker refund balance
+///   [1] maker_refund_owner (readonly)
+pub fn cancel_escrow<'info>(ctx: Context<'_, '_, '_, 'info, CancelEscrow<'info>>) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    let vault = &mut ctx.accounts.vault;
+    let maker_refund = &mut ctx.accounts.maker_refund;
+
+    require!(vault.state != AccountState::Frozen, CustomError::AccountFrozen);
+    require!(maker_refund.state != AccountState::Frozen, CustomError::AccountFrozen);
+
+    let is_expired = match escrow.expiry {
+        COption::Some(expiry) => Clock::get()?.unix_timestamp >= expiry,
+        COption::None => false,
+    };
+    let multisig_signers = if is_expired {
+        if !ctx.accounts.authority.is_signer {
+            return Err(ProgramError::MissingRequiredSignature.into());
+        }
+        0
+    } else {
+        validate_owner(&escrow.maker, &ctx.accounts.authority, ctx.remaining_accounts)?
+    };
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
+
+    let refunded = vault.amount;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_refund_balance = e_add(cpi_ctx, maker_refund.amount, refunded, 0u8)?;
+    maker_refund.amount = new_refund_balance;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    vault.amount = as_euint128(cpi_ctx2, 0)?;
+
+    let system_program = ctx.accounts.system_program.to_account_info();
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, new_refund_balance, maker_refund.owner, 0)?;
+
+    allow_auditor(
+        &inco, &signer, &system_program, remaining_accounts,
+        &ctx.accounts.mint_a, &[new_refund_balance],
+    )?;
+
+    emit!(EscrowCancelledEvent {
+        escrow: escrow.key(),
+        refunded,
+    });
+
+    Ok(())
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[account]
+pub struct IncoEscrow {
+    pub maker: Pubkey,
+    pub seed: u64,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault: Pubkey,
+    pub maker_refund: Pubkey,
+    pub maker_receive: Pubkey,
+    pub taker: COption<Pubkey>,
+    pub offered: Euint128,
+    pub requested: Euint128,
+    pub expiry: COption<i64>,
+    pub bump: u8,
+}
+
+impl IncoEscrow {
+    pub const LEN: usize = 32 + 8 + 32 + 32 + 32 + 32 + 32 + 36 + 32 + 32 + 12 + 1; // 313 bytes
+}
+
+// ========== ACCOUNT CONTEXTS ==========
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeEscrow<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + IncoEscrow::LEN,
+        seeds = [ESCROW_SEED, maker.key().as_ref(), &seed.to_le_bytes()],
+        bump,
+    )]
+    pub escrow: Account<'info, IncoEscrow>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + IncoAccount::LEN,
+        seeds = [ESCROW_VAULT_SEED, escrow.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, IncoAccount>,
+    #[account(
+        mut,
+        constraint = maker_source.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+        constraint = maker_source.mint == mint_a.key() @ CustomError::MintMismatch,
+        constraint = maker_source.owner == maker.key() @ CustomError::OwnerMismatch,
+    )]
+    pub maker_source: Account<'info, IncoAccount>,
+    #[account(
+        constraint = maker_receive.state == AccountState::Initialized @ CustomError::UninitializedState,
+        constraint = maker_receive.mint == mint_b.key() @ CustomError::MintMismatch,
+        constraint = maker_receive.owner == maker.key() @ CustomError::OwnerMismatch,
+    )]
+    pub maker_receive: Account<'info, IncoAccount>,
+    #[account(constraint = mint_a.is_initialized @ CustomError::UninitializedState)]
+    pub mint_a: Account<'info, IncoMint>,
+    #[account(
+        constraint = mint_b.is_initialized @ CustomError::UninitializedState,
+        constraint = mint_b.key() != mint_a.key() @ IncoEscrowError::SameMint,
+    )]
+    pub mint_b: Account<'info, IncoMint>,
+    /// CHECK: Maker, owner of both maker accounts (single key or IncoMultisig)
+    #[account(mut)]
+    pub maker: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FillEscrow<'info> {
+    #[account(
+        seeds = [ESCROW_SEED, escrow.maker.as_ref(), &escrow.seed.to_le_bytes()],
+        bump = escrow.bump,
+        has_one = vault,
+        has_one = maker_receive,
+        has_one = mint_a,
+        has_one = mint_b,
+    )]
+    pub escrow: Account<'info, IncoEscrow>,
+    #[account(mut)]
+    pub vault: Account<'info, IncoAccount>,
+    #[account(mut)]
+    pub maker_receive: Account<'info, IncoAccount>,
+    #[account(
+        mut,
+        constraint = taker_source.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+        constraint = taker_source.mint == mint_b.key() @ CustomError::MintMismatch,
+        constraint = taker_source.key() != maker_receive.key() @ IncoEscrowError::SameAccount,
+    )]
+    pub taker_source: Account<'info, IncoAccount>,
+    #[account(
+        mut,
+        constraint = taker_receive.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+        constraint = taker_receive.mint == mint_a.key() @ CustomError::MintMismatch,
+        constraint = taker_receive.key() != vault.key() @ IncoEscrowError::SameAccount,
+    )]
+    pub taker_receive: Account<'info, IncoAccount>,
+    pub mint_a: Account<'info, IncoMint>,
+    pub mint_b: Account<'info, IncoMint>,
+    /// CHECK: Taker, owner of taker_source (single key or IncoMultisig)
+    #[account(mut)]
+    pub taker: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEscrow<'info> {
+    #[account(
+        mut,
+        close = maker,
+        seeds = [ESCROW_SEED, escrow.maker.as_ref(), &escrow.seed.to_le_bytes()],
+        bump = escrow.bump,
+        has_one = maker,
+        has_one = vault,
+        has_one = maker_refund,
+        has_one = mint_a,
+    )]
+    pub escrow: Account<'info, IncoEscrow>,
+    #[account(mut, close = maker)]
+    pub vault: Account<'info, IncoAccount>,
+    #[account(
+        mut,
+        constraint = maker_refund.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+    )]
+    pub maker_refund: Account<'info, IncoAccount>,
+    pub mint_a: Account<'info, IncoMint>,
+    /// CHECK: Maker, receives the rent of the escrow and vault
+    #[account(mut)]
+    pub maker: UncheckedAccount<'info>,
+    /// CHECK: The maker (single key or IncoMultisig) before expiry, any signer after
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ========== ERROR CODES ==========
+#[error_code]
+pub enum IncoEscrowError {
+    #[msg("Escrow has expired")]
+    EscrowExpired,
+    #[msg("Escrow is reserved for a different taker")]
+    TakerMismatch,
+    #[msg("Escrow mints must differ")]
+    SameMint,
+    #[msg("Escrow accounts must differ")]
+    SameAccount,
+}
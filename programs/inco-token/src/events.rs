@@ -0,0 +1,340 @@
+use anchor_lang::prelude::*;
+use inco_lightning::types::Euint128;
+use crate::{AccountState, AuthorityType, ExtensionType};
+
+// ========== TOKEN EVENTS ==========
+
+#[event]
+pub struct InitializeMintEvent {
+    pub mint: Pubkey,
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+    pub decimals: u8,
+    pub supply: Euint128,
+}
+
+#[event]
+pub struct InitializeAccountEvent {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: Euint128,
+}
+
+#[event]
+pub struct InitializeMultisigEvent {
+    pub multisig: Pubkey,
+    pub m: u8,
+    pub n: u8,
+}
+
+#[event]
+pub struct MintToEvent {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub amount: Euint128,
+    pub new_balance: Euint128,
+    pub new_supply: Euint128,
+}
+
+#[event]
+pub struct TransferEvent {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+    pub amount: Euint128,
+    pub new_source_balance: Euint128,
+    pub new_destination_balance: Euint128,
+    pub delegated_amount: Euint128,
+}
+
+#[event]
+pub struct BurnEvent {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub amount: Euint128,
+    pub new_balance: Euint128,
+    pub new_supply: Euint128,
+    pub delegated_amount: Euint128,
+}
+
+#[event]
+pub struct ApproveEvent {
+    pub source: Pubkey,
+    pub delegate: Pubkey,
+    pub delegated_amount: Euint128,
+}
+
+#[event]
+pub struct RevokeEvent {
+    pub source: Pubkey,
+    pub delegated_amount: Euint128,
+}
+
+#[event]
+pub struct FreezeEvent {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct ThawEvent {
+    pub account: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct CloseEvent {
+    pub account: Pubkey,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct AuthorityChangedEvent {
+    pub owned: Pubkey,
+    pub authority_type: AuthorityType,
+    pub new_authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct AuditorChangedEvent {
+    pub mint: Pubkey,
+    pub auditor: Option<Pubkey>,
+}
+
+#[event]
+pub struct MaxSupplySetEvent {
+    pub mint: Pubkey,
+    pub max_supply: Euint128,
+}
+
+#[event]
+pub struct SyncNativeEvent {
+    pub account: Pubkey,
+    pub lamports: u64,
+    pub new_balance: Euint128,
+    pub new_supply: Euint128,
+}
+
+#[event]
+pub struct UnwrapNativeEvent {
+    pub account: Pubkey,
+    pub destination: Pubkey,
+    pub lamports: u64,
+    pub new_balance: Euint128,
+    pub new_supply: Euint128,
+}
+
+// ========== EXTENSION EVENTS ==========
+
+#[event]
+pub struct ReallocateEvent {
+    pub owned: Pubkey,
+    pub extension_types: Vec<ExtensionType>,
+    pub data_len: u64,
+}
+
+#[event]
+pub struct MetadataPointerEvent {
+    pub mint: Pubkey,
+    pub authority: Option<Pubkey>,
+    pub metadata_address: Option<Pubkey>,
+}
+
+#[event]
+pub struct TransferHookEvent {
+    pub mint: Pubkey,
+    pub authority: Option<Pubkey>,
+    pub program_id: Option<Pubkey>,
+}
+
+#[event]
+pub struct DefaultAccountStateEvent {
+    pub mint: Pubkey,
+    pub state: AccountState,
+}
+
+#[event]
+pub struct NonTransferableEvent {
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct PermanentDelegateEvent {
+    pub mint: Pubkey,
+    pub delegate: Option<Pubkey>,
+}
+
+#[event]
+pub struct InterestRateEvent {
+    pub mint: Pubkey,
+    pub rate: i16,
+    pub pre_update_average_rate: i16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UiAmountEvent {
+    pub account: Pubkey,
+    pub ui_amount: Euint128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintCloseAuthorityEvent {
+    pub mint: Pubkey,
+    pub close_authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct MemoTransferEvent {
+    pub account: Pubkey,
+    pub require_incoming_transfer_memos: bool,
+}
+
+#[event]
+pub struct TransferFeeConfigEvent {
+    pub mint: Pubkey,
+    pub transfer_fee_basis_points: u16,
+    pub maximum_fee: Euint128,
+    /// First epoch the fee applies to
+    pub epoch: u64,
+}
+
+#[event]
+pub struct TransferFeeWithheldEvent {
+    pub destination: Pubkey,
+    pub fee: Euint128,
+    pub withheld_amount: Euint128,
+}
+
+#[event]
+pub struct HarvestWithheldEvent {
+    pub mint: Pubkey,
+    pub sources: Vec<Pubkey>,
+    pub withheld_amount: Euint128,
+}
+
+#[event]
+pub struct WithdrawWithheldEvent {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: Euint128,
+    pub new_destination_balance: Euint128,
+}
+
+// ========== ESCROW EVENTS ==========
+
+#[event]
+pub struct EscrowCreatedEvent {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub offered: Euint128,
+    pub requested: Euint128,
+    pub taker: Option<Pubkey>,
+    pub expiry: Option<i64>,
+}
+
+#[event]
+pub struct EscrowFilledEvent {
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub paid: Euint128,
+    pub released: Euint128,
+}
+
+#[event]
+pub struct EscrowCancelledEvent {
+    pub escrow: Pubkey,
+    pub refunded: Euint128,
+}
+
+// ========== VESTING EVENTS ==========
+
+#[event]
+pub struct VestingCreatedEvent {
+    pub vesting: Pubkey,
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub total: Euint128,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+    pub period: i64,
+}
+
+#[event]
+pub struct VestingClaimEvent {
+    pub vesting: Pubkey,
+    pub destination: Pubkey,
+    pub amount: Euint128,
+    pub claimed: Euint128,
+    pub is_complete: bool,
+}
+
+// ========== MEMO EVENTS ==========
+
+#[event]
+pub struct MemoEvent {
+    pub authority: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub recipients: Vec<Pubkey>,
+    pub memo: Euint128,
+}
+
+/// Handles of a long memo's chunks, in sequence order
+#[event]
+pub struct MemoChunksEvent {
+    pub authority: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub recipient: Option<Pubkey>,
+    pub chunks: Vec<Euint128>,
+}
+
+// ========== METADATA EVENTS ==========
+
+#[event]
+pub struct MetadataCreatedEvent {
+    pub metadata: Pubkey,
+    pub mint: Pubkey,
+    pub update_authority: Pubkey,
+}
+
+#[event]
+pub struct MetadataUpdatedEvent {
+    pub metadata: Pubkey,
+    pub update_authority: Pubkey,
+}
+
+#[event]
+pub struct MasterEditionCreatedEvent {
+    pub edition: Pubkey,
+    pub metadata: Pubkey,
+    pub max_supply: Option<u64>,
+}
+
+#[event]
+pub struct EditionPrintedEvent {
+    pub edition: Pubkey,
+    pub master_edition: Pubkey,
+    pub edition_number: u64,
+    pub supply: u64,
+}
+
+#[event]
+pub struct CreatorVerificationEvent {
+    pub metadata: Pubkey,
+    pub creator: Pubkey,
+    pub verified: bool,
+}
+
+#[event]
+pub struct CollectionVerificationEvent {
+    pub metadata: Pubkey,
+    pub collection: Pubkey,
+    pub verified: bool,
+}
@@ -0,0 +1,476 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use inco_lightning::types::Euint128;
+use crate::token_2022::INTEREST_FACTOR_BITS;
+use crate::{AccountState, COption, CustomError, IncoAccount, IncoMint};
+
+// ========== TLV LAYOUT ==========
+
+/// Extensions follow the fixed-size base data as type-length-value entries:
+/// [type: u16 LE][length: u16 LE][value: length bytes], repeated. A zero type
+/// marks the start of free space. Accounts created at their base size have no
+/// extensions, so existing layouts are unchanged.
+pub const TLV_HEADER_LEN: usize = 4;
+/// Start of the extensions of an IncoMint. Fixed rather than derived from
+/// IncoMint::LEN, with zeroed room after the base data, so that new IncoMint
+/// fields never move the extensions of existing mints
+pub const MINT_EXTENSIONS_OFFSET: usize = 256;
+/// Start of the extensions of an IncoAccount, fixed for the same reason
+pub const ACCOUNT_EXTENSIONS_OFFSET: usize = 256;
+
+const _: () = assert!(8 + IncoMint::LEN <= MINT_EXTENSIONS_OFFSET);
+const _: () = assert!(8 + IncoAccount::LEN <= ACCOUNT_EXTENSIONS_OFFSET);
+
+#[repr(u16)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtensionType {
+    Uninitialized = 0,
+    MetadataPointer = 1,
+    TransferFeeConfig = 2,
+    TransferFeeAmount = 3,
+    TransferHook = 4,
+    DefaultAccountState = 5,
+    NonTransferable = 6,
+    PermanentDelegate = 7,
+    InterestBearingConfig = 8,
+    MintCloseAuthority = 9,
+    MemoTransfer = 10,
+    NonTransferableAccount = 11,
+}
+
+impl ExtensionType {
+    pub fn from_u16(value: u16) -> Option<Self> {
+        match value {
+            0 => Some(ExtensionType::Uninitialized),
+            1 => Some(ExtensionType::MetadataPointer),
+            2 => Some(ExtensionType::TransferFeeConfig),
+            3 => Some(ExtensionType::TransferFeeAmount),
+            4 => Some(ExtensionType::TransferHook),
+            5 => Some(ExtensionType::DefaultAccountState),
+            6 => Some(ExtensionType::NonTransferable),
+            7 => Some(ExtensionType::PermanentDelegate),
+            8 => Some(ExtensionType::InterestBearingConfig),
+            9 => Some(ExtensionType::MintCloseAuthority),
+            10 => Some(ExtensionType::MemoTransfer),
+            11 => Some(ExtensionType::NonTransferableAccount),
+            _ => None,
+        }
+    }
+
+    /// Bytes reserved for the value of this extension
+    pub fn value_len(&self) -> usize {
+        match self {
+            ExtensionType::Uninitialized => 0,
+            ExtensionType::MetadataPointer => MetadataPointer::LEN,
+            ExtensionType::TransferFeeConfig => TransferFeeConfig::LEN,
+            ExtensionType::TransferFeeAmount => TransferFeeAmount::LEN,
+            ExtensionType::TransferHook => TransferHook::LEN,
+            ExtensionType::DefaultAccountState => DefaultAccountState::LEN,
+            ExtensionType::NonTransferable => NonTransferable::LEN,
+            ExtensionType::PermanentDelegate => PermanentDelegate::LEN,
+            ExtensionType::InterestBearingConfig => InterestBearingConfig::LEN,
+            ExtensionType::MintCloseAuthority => MintCloseAuthority::LEN,
+            ExtensionType::MemoTransfer => MemoTransfer::LEN,
+            ExtensionType::NonTransferableAccount => NonTransferableAccount::LEN,
+        }
+    }
+
+    pub fn is_mint_extension(&self) -> bool {
+        matches!(
+            self,
+            ExtensionType::MetadataPointer
+                | ExtensionType::TransferFeeConfig
+                | ExtensionType::TransferHook
+                | ExtensionType::DefaultAccountState
+                | ExtensionType::NonTransferable
+                | ExtensionType::PermanentDelegate
+                | ExtensionType::InterestBearingConfig
+                | ExtensionType::MintCloseAuthority
+        )
+    }
+
+    pub fn is_account_extension(&self) -> bool {
+        matches!(
+            self,
+            ExtensionType::TransferFeeAmount | ExtensionType::MemoTransfer | ExtensionType::NonTransferableAccount
+        )
+    }
+}
+
+/// Fixed-size extension value stored in a TLV entry
+pub trait Extension: AnchorSerialize + AnchorDeserialize {
+    const TYPE: ExtensionType;
+    /// Upper bound of the serialized value; the entry is zero padded to it
+    const LEN: usize;
+}
+
+// ========== EXTENSION VALUES ==========
+
+/// Mint extension pointing at the account holding the mint's metadata
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct MetadataPointer {
+    pub authority: COption<Pubkey>,
+    pub metadata_address: COption<Pubkey>,
+}
+
+impl Extension for MetadataPointer {
+    const TYPE: ExtensionType = ExtensionType::MetadataPointer;
+    const LEN: usize = 36 + 36;
+}
+
+/// Transfer fee in effect from `epoch` on: basis points of the amount, capped
+/// at an encrypted maximum
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: Euint128,
+    pub transfer_fee_basis_points: u16,
+}
+
+impl TransferFee {
+    pub const LEN: usize = 8 + 32 + 2;
+}
+
+/// Mint extension charging a fee on transfer_checked. A new fee is scheduled as
+/// `newer_transfer_fee` and `older_transfer_fee` applies until its epoch.
+/// Fees harvested from accounts are accumulated in `withheld_amount`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransferFeeConfig {
+    pub transfer_fee_config_authority: COption<Pubkey>,
+    pub withdraw_withheld_authority: COption<Pubkey>,
+    pub withheld_amount: Euint128,
+    pub older_transfer_fee: TransferFee,
+    pub newer_transfer_fee: TransferFee,
+}
+
+impl Extension for TransferFeeConfig {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeConfig;
+    const LEN: usize = 36 + 36 + 32 + 2 * TransferFee::LEN;
+}
+
+impl TransferFeeConfig {
+    /// Fee charged on transfers during `epoch`
+    pub fn get_epoch_fee(&self, epoch: u64) -> &TransferFee {
+        if epoch >= self.newer_transfer_fee.epoch {
+            &self.newer_transfer_fee
+        } else {
+            &self.older_transfer_fee
+        }
+    }
+}
+
+/// Account extension holding the fees withheld from incoming transfers
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct TransferFeeAmount {
+    pub withheld_amount: Euint128,
+}
+
+impl Extension for TransferFeeAmount {
+    const TYPE: ExtensionType = ExtensionType::TransferFeeAmount;
+    const LEN: usize = 32;
+}
+
+/// Account extension requiring incoming transfers to carry a memo
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct MemoTransfer {
+    pub require_incoming_transfer_memos: bool,
+}
+
+impl Extension for MemoTransfer {
+    const TYPE: ExtensionType = ExtensionType::MemoTransfer;
+    const LEN: usize = 1;
+}
+
+/// Mint extension naming a program that transfer and transfer_checked invoke
+/// on every transfer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct TransferHook {
+    pub authority: COption<Pubkey>,
+    pub program_id: COption<Pubkey>,
+}
+
+impl Extension for TransferHook {
+    const TYPE: ExtensionType = ExtensionType::TransferHook;
+    const LEN: usize = 36 + 36;
+}
+
+/// Mint extension setting the state new token accounts start in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct DefaultAccountState {
+    pub state: AccountState,
+}
+
+impl Extension for DefaultAccountState {
+    const TYPE: ExtensionType = ExtensionType::DefaultAccountState;
+    const LEN: usize = 1;
+}
+
+/// Mint extension binding tokens to the account they are minted to: they can be
+/// burned but not transferred or delegated
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct NonTransferable {}
+
+impl Extension for NonTransferable {
+    const TYPE: ExtensionType = ExtensionType::NonTransferable;
+    const LEN: usize = 0;
+}
+
+/// Account extension marking an account of a non-transferable mint, whose owner
+/// cannot be changed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct NonTransferableAccount {}
+
+impl Extension for NonTransferableAccount {
+    const TYPE: ExtensionType = ExtensionType::NonTransferableAccount;
+    const LEN: usize = 0;
+}
+
+/// Mint extension naming a delegate that may transfer or burn from any account
+/// of the mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct PermanentDelegate {
+    pub delegate: COption<Pubkey>,
+}
+
+impl Extension for PermanentDelegate {
+    const TYPE: ExtensionType = ExtensionType::PermanentDelegate;
+    const LEN: usize = 36;
+}
+
+/// Mint extension naming who may close the mint once its supply is zero
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct MintCloseAuthority {
+    pub close_authority: COption<Pubkey>,
+}
+
+impl Extension for MintCloseAuthority {
+    const TYPE: ExtensionType = ExtensionType::MintCloseAuthority;
+    const LEN: usize = 36;
+}
+
+/// Mint extension accruing continuously compounded interest on balances for
+/// display. Rates are in basis points per year; balances themselves never change.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub struct InterestBearingConfig {
+    pub rate_authority: COption<Pubkey>,
+    pub initialization_timestamp: i64,
+    /// Time-weighted average of the rates before the last update
+    pub pre_update_average_rate: i16,
+    pub last_update_timestamp: i64,
+    pub current_rate: i16,
+}
+
+impl Extension for InterestBearingConfig {
+    const TYPE: ExtensionType = ExtensionType::InterestBearingConfig;
+    const LEN: usize = 36 + 8 + 2 + 8 + 2;
+}
+
+/// Seconds in a Julian year, the compounding period of interest rates
+const SECONDS_PER_YEAR: u128 = 60 * 60 * 24 * 36_524 / 100;
+
+/// e^(numerator / denominator) with INTEREST_FACTOR_BITS fractional bits, summed
+/// term by term in integers so every validator computes the same factor
+fn exp_fixed(numerator: u128, denominator: u128) -> Option<u128> {
+    let mut sum = 1u128 << INTEREST_FACTOR_BITS;
+    let mut term = sum;
+    let mut n = 1u128;
+    while term > 0 {
+        term = term.checked_mul(numerator)? / denominator.checked_mul(n)?;
+        sum = sum.checked_add(term)?;
+        n += 1;
+    }
+    Some(sum)
+}
+
+impl InterestBearingConfig {
+    /// Rate that, applied since initialization, accrues as much as the rates so far
+    pub fn time_weighted_average_rate(&self, now: i64) -> Option<i16> {
+        let pre_update_timespan = i128::from(self.last_update_timestamp) - i128::from(self.initialization_timestamp);
+        let post_update_timespan = i128::from(now) - i128::from(self.last_update_timestamp);
+        let total_timespan = pre_update_timespan.checked_add(post_update_timespan)?;
+        if total_timespan == 0 {
+            return Some(self.current_rate);
+        }
+        let total_sum = i128::from(self.pre_update_average_rate)
+            .checked_mul(pre_update_timespan)?
+            .checked_add(i128::from(self.current_rate).checked_mul(post_update_timespan)?)?;
+        i16::try_from(total_sum / total_timespan).ok()
+    }
+
+    /// Factor by which balances have grown since initialization, with
+    /// INTEREST_FACTOR_BITS fractional bits. None if it is 2^64 or more, where a
+    /// u64 balance times the factor would no longer fit in 128 bits.
+    pub fn accrued_factor(&self, now: i64) -> Option<u128> {
+        // Basis-point seconds; both periods compound into a single exponent
+        let exponent = i128::from(self.pre_update_average_rate)
+            * (i128::from(self.last_update_timestamp) - i128::from(self.initialization_timestamp))
+            + i128::from(self.current_rate) * (i128::from(now) - i128::from(self.last_update_timestamp));
+        let growth = exp_fixed(exponent.unsigned_abs(), SECONDS_PER_YEAR * 10_000);
+        if exponent >= 0 {
+            growth.filter(|growth| *growth < 1u128 << 64)
+        } else {
+            Some(growth.map_or(0, |growth| (1u128 << (2 * INTEREST_FACTOR_BITS)) / growth))
+        }
+    }
+}
+
+// ========== TLV HELPERS ==========
+
+/// Bytes taken by the TLV entries for `extension_types`
+pub fn extension_space(extension_types: &[ExtensionType]) -> usize {
+    extension_types
+        .iter()
+        .map(|extension_type| TLV_HEADER_LEN + extension_type.value_len())
+        .sum()
+}
+
+/// Account size of an IncoMint with `extension_types`
+pub fn mint_space(extension_types: &[ExtensionType]) -> usize {
+    if extension_types.is_empty() {
+        return 8 + IncoMint::LEN;
+    }
+    MINT_EXTENSIONS_OFFSET + extension_space(extension_types)
+}
+
+/// Account size of an IncoAccount with `extension_types`
+pub fn account_space(extension_types: &[ExtensionType]) -> usize {
+    if extension_types.is_empty() {
+        return 8 + IncoAccount::LEN;
+    }
+    ACCOUNT_EXTENSIONS_OFFSET + extension_space(extension_types)
+}
+
+struct TlvEntry {
+    extension_type: ExtensionType,
+    value_start: usize,
+    value_len: usize,
+}
+
+/// Initialized entries and the offset where free space starts
+fn parse_entries(data: &[u8], offset: usize) -> Result<(Vec<TlvEntry>, usize)> {
+    let mut entries = Vec::new();
+    let mut cursor = offset;
+    while cursor + TLV_HEADER_LEN <= data.len() {
+        let raw_type = u16::from_le_bytes([data[cursor], data[cursor + 1]]);
+        if raw_type == ExtensionType::Uninitialized as u16 {
+            break;
+        }
+        let extension_type = ExtensionType::from_u16(raw_type).ok_or(CustomError::InvalidExtensionData)?;
+        let value_len = u16::from_le_bytes([data[cursor + 2], data[cursor + 3]]) as usize;
+        let value_start = cursor + TLV_HEADER_LEN;
+        require!(value_start + value_len <= data.len(), CustomError::InvalidExtensionData);
+
+        entries.push(TlvEntry { extension_type, value_start, value_len });
+        cursor = value_start + value_len;
+    }
+    Ok((entries, cursor))
+}
+
+/// Types of the extensions initialized in `data`
+pub fn get_extension_types(data: &[u8], offset: usize) -> Result<Vec<ExtensionType>> {
+    let (entries, _) = parse_entries(data, offset)?;
+    Ok(entries.iter().map(|entry| entry.extension_type).collect())
+}
+
+/// Read extension `E`, or None when it is not initialized
+pub fn get_extension<E: Extension>(data: &[u8], offset: usize) -> Result<Option<E>> {
+    let (entries, _) = parse_entries(data, offset)?;
+    match entries.iter().find(|entry| entry.extension_type == E::TYPE) {
+        Some(entry) => {
+            let mut value = &data[entry.value_start..entry.value_start + entry.value_len];
+            let extension = E::deserialize(&mut value).map_err(|_| CustomError::InvalidExtensionData)?;
+            Ok(Some(extension))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Write extension `E` into the free space, which reallocate must have reserved
+pub fn init_extension<E: Extension>(data: &mut [u8], offset: usize, extension: &E) -> Result<()> {
+    let (entries, free_start) = parse_entries(data, offset)?;
+    require!(
+        !entries.iter().any(|entry| entry.extension_type == E::TYPE),
+        CustomError::ExtensionAlreadyInitialized
+    );
+    let value_start = free_start + TLV_HEADER_LEN;
+    require!(value_start + E::LEN <= data.len(), CustomError::InsufficientExtensionSpace);
+
+    data[free_start..free_start + 2].copy_from_slice(&(E::TYPE as u16).to_le_bytes());
+    data[free_start + 2..value_start].copy_from_slice(&(E::LEN as u16).to_le_bytes());
+    write_value(&mut data[value_start..value_start + E::LEN], extension)
+}
+
+/// Overwrite the value of an initialized extension `E`
+pub fn set_extension<E: Extension>(data: &mut [u8], offset: usize, extension: &E) -> Result<()> {
+    let (entries, _) = parse_entries(data, offset)?;
+    let entry = entries
+        .iter()
+        .find(|entry| entry.extension_type == E::TYPE)
+        .ok_or(CustomError::ExtensionNotFound)?;
+    write_value(&mut data[entry.value_start..entry.value_start + entry.value_len], extension)
+}
+
+fn write_value<E: Extension>(mut slot: &mut [u8], extension: &E) -> Result<()> {
+    slot.fill(0);
+    extension.serialize(&mut slot).map_err(|_| CustomError::InvalidExtensionData)?;
+    Ok(())
+}
+
+/// Grow `target` so that every type in `extension_types` that is not yet
+/// initialized has room in the free space, topping up rent from `payer`.
+/// Returns the new data length.
+pub fn reallocate_extensions<'info>(
+    target: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    offset: usize,
+    extension_types: &[ExtensionType],
+) -> Result<usize> {
+    let (missing, free_start) = {
+        let data = target.try_borrow_data()?;
+        let (entries, free_start) = parse_entries(&data, offset)?;
+        let mut missing: Vec<ExtensionType> = Vec::new();
+        for extension_type in extension_types {
+            require!(*extension_type != ExtensionType::Uninitialized, CustomError::InvalidExtensionType);
+            let initialized = entries.iter().any(|entry| entry.extension_type == *extension_type);
+            if !initialized && !missing.contains(extension_type) {
+                missing.push(*extension_type);
+            }
+        }
+        (missing, free_start)
+    };
+
+    let old_len = target.data_len();
+    let new_len = free_start + extension_space(&missing);
+    if new_len <= old_len {
+        return Ok(old_len);
+    }
+    grow_account(target, payer, system_program, new_len)?;
+    Ok(new_len)
+}
+
+/// Grow `target` to `new_len` bytes, zeroing the new space and topping up rent
+/// from `payer`
+pub fn grow_account<'info>(
+    target: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let old_len = target.data_len();
+    let shortfall = Rent::get()?.minimum_balance(new_len).saturating_sub(target.lamports());
+    if shortfall > 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: payer.clone(),
+                to: target.clone(),
+            }
+        );
+        transfer(cpi_ctx, shortfall)?;
+    }
+
+    target.realloc(new_len, false)?;
+    target.try_borrow_mut_data()?[old_len..].fill(0);
+    Ok(())
+}
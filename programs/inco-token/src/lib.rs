@@ -8,6 +8,13 @@ pub mod associated_token;
 pub mod memo;
 pub mod metadata;
 pub mod token_2022;
+pub mod events;
+pub mod allowance;
+pub mod extension;
+pub mod escrow;
+pub mod vesting;
+#[cfg(feature = "client")]
+pub mod client;
 
 // Re-export everything
 pub use token::*;
@@ -15,19 +22,40 @@ pub use memo::*;
 pub use associated_token::*;
 pub use metadata::*;
 pub use token_2022::*;
+pub use events::*;
+pub use allowance::*;
+pub use extension::*;
+pub use escrow::*;
+pub use vesting::*;
 
 declare_id!("4cyJHzecVWuU2xux6bCAPAhALKQT8woBh4Vx3AGEGe5N");
 
 // ========== SHARED TYPES ==========
 
 #[repr(u8)]
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum AccountState {
     Uninitialized = 0,
     Initialized = 1,
     Frozen = 2,
 }
 
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthorityType {
+    MintTokens = 0,
+    FreezeAccount = 1,
+    AccountOwner = 2,
+    CloseAccount = 3,
+}
+
+/// Amount given in plaintext or as a client-encrypted ciphertext
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AmountInput {
+    Plaintext(u128),
+    Encrypted { ciphertext: Vec<u8>, input_type: u8 },
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub enum COption<T> {
     None,
@@ -50,6 +78,15 @@ impl<T> COption<T> {
     }
 }
 
+impl<T: Clone> COption<T> {
+    pub fn to_option(&self) -> Option<T> {
+        match self {
+            COption::Some(value) => Some(value.clone()),
+            COption::None => None,
+        }
+    }
+}
+
 // ========== SHARED ACCOUNT STRUCTURES ==========
 
 #[account]
@@ -59,10 +96,12 @@ pub struct IncoMint {
     pub decimals: u8,
     pub is_initialized: bool,
     pub freeze_authority: COption<Pubkey>,
+    pub auditor: COption<Pubkey>,
+    pub max_supply: COption<inco_lightning::types::Euint128>,
 }
 
 impl IncoMint {
-    pub const LEN: usize = 36 + 32 + 1 + 1 + 36; // 106 bytes
+    pub const LEN: usize = 36 + 32 + 1 + 1 + 36 + 36 + 36; // 178 bytes
 }
 
 #[account]
@@ -81,6 +120,27 @@ impl IncoAccount {
     pub const LEN: usize = 32 + 32 + 32 + 36 + 1 + 12 + 32 + 36; // 213 bytes
 }
 
+pub const MAX_SIGNERS: usize = 11;
+
+/// Seed of the native (wrapped SOL) mint PDA
+pub const NATIVE_MINT_SEED: &[u8] = b"native_mint";
+
+/// M-of-N multisig usable wherever a mint or account authority is expected.
+/// The signing members are passed as the leading remaining_accounts.
+/// Authority accounts are therefore not `Signer`s, and the IDL does not mark them
+/// as signing: a single-key authority must be flagged as a signer by the client.
+#[account]
+pub struct IncoMultisig {
+    pub m: u8,
+    pub n: u8,
+    pub is_initialized: bool,
+    pub signers: [Pubkey; MAX_SIGNERS],
+}
+
+impl IncoMultisig {
+    pub const LEN: usize = 1 + 1 + 1 + 32 * MAX_SIGNERS; // 355 bytes
+}
+
 #[program]
 pub mod inco_token {
     use super::*;
@@ -100,7 +160,35 @@ pub mod inco_token {
         token::initialize_account(ctx)
     }
 
-    /// Mint tokens to an account
+    /// Initialize an M-of-N multisig authority
+    /// remaining_accounts: [signer_1, ..., signer_n]
+    pub fn initialize_multisig<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeMultisig<'info>>,
+        m: u8
+    ) -> Result<()> {
+        token::initialize_multisig(ctx, m)
+    }
+
+    pub fn initialize_native_mint(ctx: Context<InitializeNativeMint>) -> Result<()> {
+        token::initialize_native_mint(ctx)
+    }
+
+    /// Credit lamports deposited into a native account
+    /// remaining_accounts: [allowance_account, owner_address]
+    pub fn sync_native<'info>(ctx: Context<'_, '_, '_, 'info, SyncNative<'info>>) -> Result<()> {
+        token::sync_native(ctx)
+    }
+
+    /// Unwrap a native account's attested balance back to lamports
+    /// remaining_accounts: [allowance_account, owner_address]
+    pub fn unwrap_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, UnwrapNative<'info>>,
+        balance_plaintext: Vec<u8>
+    ) -> Result<()> {
+        token::unwrap_native(ctx, balance_plaintext)
+    }
+
+    /// Mint tokens to an account; mints zero instead when the amount would exceed the max supply
     /// remaining_accounts: [allowance_account, owner_address]
     pub fn mint_to<'info>(
         ctx: Context<'_, '_, '_, 'info, IncoMintTo<'info>>,
@@ -112,6 +200,9 @@ pub mod inco_token {
 
     /// Transfer tokens between accounts
     /// remaining_accounts: [source_allowance, source_owner, dest_allowance, dest_owner]
+    /// plus, when a delegate signs: [owner_delegated_allowance, source_owner, delegate_delegated_allowance, delegate]
+    /// then, when the destination requires memos: [instructions_sysvar]
+    /// and, when the mint has a transfer hook: [hook_program, extra_account_metas, extra_accounts...]
     pub fn transfer<'info>(
         ctx: Context<'_, '_, '_, 'info, IncoTransfer<'info>>,
         ciphertext: Vec<u8>,
@@ -120,6 +211,17 @@ pub mod inco_token {
         token::transfer(ctx, ciphertext, input_type)
     }
 
+    /// Transfer to many destinations with one running balance check
+    /// remaining_accounts: [dest_1, ..., dest_n, source_allowance, source_owner,
+    ///   dest_allowance_1, dest_owner_1, ..., dest_allowance_n, dest_owner_n]
+    pub fn transfer_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, IncoTransferBatch<'info>>,
+        ciphertexts: Vec<Vec<u8>>,
+        input_type: u8
+    ) -> Result<()> {
+        token::transfer_batch(ctx, ciphertexts, input_type)
+    }
+
     /// Approve a delegate
     /// remaining_accounts: [allowance_account, delegate_address]
     pub fn approve<'info>(
@@ -130,12 +232,13 @@ pub mod inco_token {
         token::approve(ctx, ciphertext, input_type)
     }
 
-    pub fn revoke(ctx: Context<IncoRevoke>) -> Result<()> {
+    pub fn revoke<'info>(ctx: Context<'_, '_, '_, 'info, IncoRevoke<'info>>) -> Result<()> {
         token::revoke(ctx)
     }
 
     /// Burn tokens
     /// remaining_accounts: [allowance_account, owner_address]
+    /// plus, when a delegate signs: [owner_delegated_allowance, owner, delegate_delegated_allowance, delegate]
     pub fn burn<'info>(
         ctx: Context<'_, '_, '_, 'info, IncoBurn<'info>>,
         ciphertext: Vec<u8>,
@@ -144,40 +247,172 @@ pub mod inco_token {
         token::burn(ctx, ciphertext, input_type)
     }
 
-    pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
+    pub fn freeze_account<'info>(ctx: Context<'_, '_, '_, 'info, FreezeAccount<'info>>) -> Result<()> {
         token::freeze_account(ctx)
     }
 
-    pub fn thaw_account(ctx: Context<ThawAccount>) -> Result<()> {
+    pub fn thaw_account<'info>(ctx: Context<'_, '_, '_, 'info, ThawAccount<'info>>) -> Result<()> {
         token::thaw_account(ctx)
     }
 
-    pub fn close_account(ctx: Context<CloseAccount>) -> Result<()> {
-        token::close_account(ctx)
+    /// Close an account with an attested zero balance
+    /// Requires a preceding Ed25519 attestation instruction for account.amount
+    pub fn close_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseAccount<'info>>,
+        balance_plaintext: Vec<u8>
+    ) -> Result<()> {
+        token::close_account(ctx, balance_plaintext)
     }
 
-    pub fn set_mint_authority(ctx: Context<SetMintAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+    pub fn set_mint_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMintAuthority<'info>>,
+        new_authority: Option<Pubkey>
+    ) -> Result<()> {
         token::set_mint_authority(ctx, new_authority)
     }
 
-    pub fn set_freeze_authority(ctx: Context<SetFreezeAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+    pub fn set_freeze_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetFreezeAuthority<'info>>,
+        new_authority: Option<Pubkey>
+    ) -> Result<()> {
         token::set_freeze_authority(ctx, new_authority)
     }
 
-    pub fn set_account_owner(ctx: Context<SetAccountOwner>, new_owner: Pubkey) -> Result<()> {
+    pub fn set_account_owner<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAccountOwner<'info>>,
+        new_owner: Pubkey
+    ) -> Result<()> {
         token::set_account_owner(ctx, new_owner)
     }
 
-    pub fn set_close_authority(ctx: Context<SetCloseAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+    pub fn set_close_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetCloseAuthority<'info>>,
+        new_authority: Option<Pubkey>
+    ) -> Result<()> {
         token::set_close_authority(ctx, new_authority)
     }
 
+    /// Set or clear the mint auditor, who is granted access to every new handle
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the mint authority is a multisig
+    pub fn set_auditor<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAuditor<'info>>,
+        auditor: Option<Pubkey>
+    ) -> Result<()> {
+        token::set_auditor(ctx, auditor)
+    }
+
+    /// Grow a mint created before the auditor or max_supply fields to the current mint size
+    pub fn migrate_mint<'info>(ctx: Context<'_, '_, '_, 'info, MigrateMint<'info>>) -> Result<()> {
+        token::migrate_mint(ctx)
+    }
+
+    /// Cap the supply once; mints that would exceed it mint zero instead
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the mint authority is a multisig,
+    /// then [allowance_account, mint_authority_address] and the auditor's allowance accounts
+    pub fn set_max_supply<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetMaxSupply<'info>>,
+        max_supply: AmountInput
+    ) -> Result<()> {
+        token::set_max_supply(ctx, max_supply)
+    }
+
+    /// Set a mint or account authority (SPL Token compatible)
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the current authority is a multisig
+    pub fn set_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAuthority<'info>>,
+        authority_type: AuthorityType,
+        new_authority: Option<Pubkey>
+    ) -> Result<()> {
+        token::set_authority(ctx, authority_type, new_authority)
+    }
+
+    // ========== ESCROW INSTRUCTIONS ==========
+
+    /// Lock an encrypted amount of mint A for an encrypted amount of mint B
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the maker is a multisig,
+    /// then allowance accounts for the maker (and the taker when one is set) and the mint auditors
+    pub fn make_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeEscrow<'info>>,
+        seed: u64,
+        offered_ciphertext: Vec<u8>,
+        requested_ciphertext: Vec<u8>,
+        input_type: u8,
+        taker: Option<Pubkey>,
+        expiry: Option<i64>
+    ) -> Result<()> {
+        escrow::make_escrow(ctx, seed, offered_ciphertext, requested_ciphertext, input_type, taker, expiry)
+    }
+
+    /// Settle both legs of an escrow atomically, on the terms the taker expects
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the taker is a multisig,
+    /// then allowance accounts for the four new balances and the mint auditors
+    pub fn fill_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, FillEscrow<'info>>,
+        expected_offered_ciphertext: Vec<u8>,
+        expected_requested_ciphertext: Vec<u8>,
+        input_type: u8
+    ) -> Result<()> {
+        escrow::fill_escrow(ctx, expected_offered_ciphertext, expected_requested_ciphertext, input_type)
+    }
+
+    /// Refund the vault to the maker and close the escrow
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the maker cancels as a multisig,
+    /// then [allowance_account, maker_refund_owner] and the mint A auditor's allowance accounts
+    pub fn cancel_escrow<'info>(ctx: Context<'_, '_, '_, 'info, CancelEscrow<'info>>) -> Result<()> {
+        escrow::cancel_escrow(ctx)
+    }
+
+    // ========== VESTING INSTRUCTIONS ==========
+
+    /// Lock an encrypted grant for a beneficiary under a vesting schedule
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the grantor is a multisig,
+    /// then allowance accounts for the grantor and beneficiary and the mint auditor
+    pub fn create_vesting<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateVesting<'info>>,
+        seed: u64,
+        ciphertext: Vec<u8>,
+        input_type: u8,
+        schedule: VestingSchedule
+    ) -> Result<()> {
+        vesting::create_vesting(ctx, seed, ciphertext, input_type, schedule)
+    }
+
+    /// Claim the unlocked part of a grant
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the beneficiary is a multisig,
+    /// then allowance accounts for the destination owner and beneficiary and the mint auditor
+    pub fn claim<'info>(ctx: Context<'_, '_, '_, 'info, Claim<'info>>) -> Result<()> {
+        vesting::claim(ctx)
+    }
+
     // ========== MEMO INSTRUCTIONS ==========
 
     pub fn build_memo(ctx: Context<BuildMemo>, encrypted_memo: Vec<u8>, input_type: u8) -> Result<()> {
         memo::build_memo(ctx, encrypted_memo, input_type)
     }
 
+    /// Log an encrypted memo and let each of `recipients` decrypt it
+    /// remaining_accounts: [signer_1, ..., signer_n, recipients..., allowance_accounts...]
+    pub fn build_memo_with_recipients<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuildMemoWithRecipients<'info>>,
+        encrypted_memo: Vec<u8>,
+        input_type: u8,
+        recipients: Vec<Pubkey>
+    ) -> Result<()> {
+        memo::build_memo_with_recipients(ctx, encrypted_memo, input_type, recipients)
+    }
+
+    /// Build a memo longer than 16 bytes from encrypted chunks, granting each chunk
+    /// to the authority, the additional signers and the recipient
+    /// remaining_accounts: [signer_1, ..., signer_n, recipient, allowance_accounts...]
+    pub fn build_long_memo<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuildLongMemo<'info>>,
+        encrypted_chunks: Vec<Vec<u8>>,
+        input_type: u8,
+        recipient: Option<Pubkey>
+    ) -> Result<()> {
+        memo::build_long_memo(ctx, encrypted_chunks, input_type, recipient)
+    }
+
     // ========== ASSOCIATED TOKEN INSTRUCTIONS ==========
 
     pub fn create(ctx: Context<Create>) -> Result<()> {
@@ -272,8 +507,203 @@ pub mod inco_token {
         token_2022::revoke_2022(ctx)
     }
 
-    pub fn close_account_2022<'info>(ctx: Context<'_, '_, '_, 'info, CloseAccount2022<'info>>) -> Result<()> {
-        token_2022::close_account_2022(ctx)
+    pub fn close_account_2022<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseAccount2022<'info>>,
+        balance_plaintext: Vec<u8>
+    ) -> Result<()> {
+        token_2022::close_account_2022(ctx, balance_plaintext)
+    }
+
+    /// Grow an account for the given account extensions
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the owner is a multisig
+    pub fn reallocate<'info>(
+        ctx: Context<'_, '_, '_, 'info, Reallocate<'info>>,
+        extension_types: Vec<ExtensionType>
+    ) -> Result<()> {
+        token_2022::reallocate(ctx, extension_types)
+    }
+
+    /// Grow a mint for the given mint extensions
+    /// remaining_accounts: [multisig_signer_1, ..., multisig_signer_m] when the mint authority is a multisig
+    pub fn reallocate_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReallocateMint<'info>>,
+        extension_types: Vec<ExtensionType>
+    ) -> Result<()> {
+        token_2022::reallocate_mint(ctx, extension_types)
+    }
+
+    pub fn initialize_metadata_pointer<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeMetadataPointer<'info>>,
+        authority: Option<Pubkey>,
+        metadata_address: Option<Pubkey>
+    ) -> Result<()> {
+        token_2022::initialize_metadata_pointer(ctx, authority, metadata_address)
+    }
+
+    pub fn update_metadata_pointer<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateMetadataPointer<'info>>,
+        metadata_address: Option<Pubkey>
+    ) -> Result<()> {
+        token_2022::update_metadata_pointer(ctx, metadata_address)
+    }
+
+    /// Invoke a program on every transfer and transfer_checked of the mint
+    pub fn initialize_transfer_hook<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeTransferHook<'info>>,
+        authority: Option<Pubkey>,
+        program_id: Option<Pubkey>
+    ) -> Result<()> {
+        token_2022::initialize_transfer_hook(ctx, authority, program_id)
+    }
+
+    pub fn update_transfer_hook<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateTransferHook<'info>>,
+        program_id: Option<Pubkey>
+    ) -> Result<()> {
+        token_2022::update_transfer_hook(ctx, program_id)
+    }
+
+    /// Start every new account of the mint in `state`, e.g. frozen until thawed after KYC
+    pub fn initialize_default_account_state<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeDefaultAccountState<'info>>,
+        state: AccountState
+    ) -> Result<()> {
+        token_2022::initialize_default_account_state(ctx, state)
+    }
+
+    pub fn update_default_account_state<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateDefaultAccountState<'info>>,
+        state: AccountState
+    ) -> Result<()> {
+        token_2022::update_default_account_state(ctx, state)
+    }
+
+    /// Make the mint's tokens non-transferable; they can still be minted and burned
+    /// Only in the transaction that initializes the mint, before any token account exists
+    pub fn initialize_non_transferable_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeNonTransferableMint<'info>>
+    ) -> Result<()> {
+        token_2022::initialize_non_transferable_mint(ctx)
+    }
+
+    /// Let a delegate transfer or burn from any account of the mint, e.g. for clawbacks
+    /// Only in the transaction that initializes the mint, before any token account exists
+    pub fn initialize_permanent_delegate<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializePermanentDelegate<'info>>,
+        delegate: Pubkey
+    ) -> Result<()> {
+        token_2022::initialize_permanent_delegate(ctx, delegate)
+    }
+
+    /// Hand over or, with None, give up the permanent delegate role
+    pub fn update_permanent_delegate<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdatePermanentDelegate<'info>>,
+        new_delegate: Option<Pubkey>
+    ) -> Result<()> {
+        token_2022::update_permanent_delegate(ctx, new_delegate)
+    }
+
+    /// Accrue interest at `rate` basis points per year on the displayed balances
+    pub fn initialize_interest_bearing_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeInterestBearingMint<'info>>,
+        rate_authority: Option<Pubkey>,
+        rate: i16
+    ) -> Result<()> {
+        token_2022::initialize_interest_bearing_mint(ctx, rate_authority, rate)
+    }
+
+    pub fn update_interest_rate<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateInterestRate<'info>>,
+        rate: i16
+    ) -> Result<()> {
+        token_2022::update_interest_rate(ctx, rate)
+    }
+
+    /// Encrypted balance with accrued interest, granted to the account owner
+    /// remaining_accounts: [allowance_account, owner_address]
+    pub fn amount_to_ui_amount<'info>(
+        ctx: Context<'_, '_, '_, 'info, AmountToUiAmount<'info>>
+    ) -> Result<inco_lightning::types::Euint128> {
+        token_2022::amount_to_ui_amount(ctx)
+    }
+
+    /// Let `close_authority` close the mint once its supply is zero
+    /// Only in the transaction that initializes the mint, before any token account exists
+    pub fn initialize_mint_close_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeMintCloseAuthority<'info>>,
+        close_authority: Option<Pubkey>
+    ) -> Result<()> {
+        token_2022::initialize_mint_close_authority(ctx, close_authority)
+    }
+
+    pub fn update_mint_close_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, UpdateMintCloseAuthority<'info>>,
+        new_authority: Option<Pubkey>
+    ) -> Result<()> {
+        token_2022::update_mint_close_authority(ctx, new_authority)
+    }
+
+    /// Close a mint whose supply is attested to be zero, returning its lamports
+    pub fn close_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseMint<'info>>,
+        supply_plaintext: Vec<u8>
+    ) -> Result<()> {
+        token_2022::close_mint(ctx, supply_plaintext)
+    }
+
+    /// Require incoming transfers to be preceded by build_memo; the account must have been reallocated for it
+    pub fn enable_required_transfer_memos<'info>(
+        ctx: Context<'_, '_, '_, 'info, RequiredTransferMemos<'info>>
+    ) -> Result<()> {
+        token_2022::enable_required_transfer_memos(ctx)
+    }
+
+    pub fn disable_required_transfer_memos<'info>(
+        ctx: Context<'_, '_, '_, 'info, RequiredTransferMemos<'info>>
+    ) -> Result<()> {
+        token_2022::disable_required_transfer_memos(ctx)
+    }
+
+    /// Charge a basis-point fee, capped at an encrypted maximum, on transfer_checked
+    /// Only in the transaction that initializes the mint, before any token account exists
+    pub fn initialize_transfer_fee_config<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeTransferFeeConfig<'info>>,
+        transfer_fee_config_authority: Option<Pubkey>,
+        withdraw_withheld_authority: Option<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: AmountInput
+    ) -> Result<()> {
+        token_2022::initialize_transfer_fee_config(
+            ctx,
+            transfer_fee_config_authority,
+            withdraw_withheld_authority,
+            transfer_fee_basis_points,
+            maximum_fee,
+        )
+    }
+
+    /// Schedule a new transfer fee, charged from TRANSFER_FEE_EPOCH_DELAY epochs on
+    pub fn set_transfer_fee<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetTransferFee<'info>>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: AmountInput
+    ) -> Result<()> {
+        token_2022::set_transfer_fee(ctx, transfer_fee_basis_points, maximum_fee)
+    }
+
+    /// Move withheld fees from the token accounts in remaining_accounts into the mint
+    pub fn harvest_withheld_to_mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, HarvestWithheldToMint<'info>>
+    ) -> Result<()> {
+        token_2022::harvest_withheld_to_mint(ctx)
+    }
+
+    /// Withdraw withheld fees from the mint and `num_sources` token accounts
+    pub fn withdraw_withheld_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawWithheldTokens<'info>>,
+        num_sources: u8
+    ) -> Result<()> {
+        token_2022::withdraw_withheld_tokens(ctx, num_sources)
     }
 }
 
@@ -320,4 +750,42 @@ pub enum CustomError {
     MintDecimalsMismatch,
     #[msg("Non-native not supported")]
     NonNativeNotSupported,
+    #[msg("Missing or invalid auditor allowance account")]
+    MissingAuditorAllowance,
+    #[msg("Allowance account does not match the handle and allowed address")]
+    InvalidAllowanceAccount,
+    #[msg("Missing allowance accounts")]
+    MissingAllowanceAccounts,
+    #[msg("Max supply already set")]
+    MaxSupplyAlreadySet,
+    #[msg("Invalid extension data")]
+    InvalidExtensionData,
+    #[msg("Extension type not supported here")]
+    InvalidExtensionType,
+    #[msg("Extension already initialized")]
+    ExtensionAlreadyInitialized,
+    #[msg("Extension not found")]
+    ExtensionNotFound,
+    #[msg("Not enough space for the extension, reallocate first")]
+    InsufficientExtensionSpace,
+    #[msg("Transfer fee basis points exceed 10000")]
+    TransferFeeExceedsMaximum,
+    #[msg("Mint charges a transfer fee, use transfer_checked")]
+    MintHasTransferFee,
+    #[msg("Mint has a transfer hook, use transfer or transfer_checked")]
+    MintHasTransferHook,
+    #[msg("Transfer hook program or extra account metas not provided")]
+    MissingTransferHookAccounts,
+    #[msg("Default account state must be initialized or frozen")]
+    InvalidDefaultAccountState,
+    #[msg("Mint is non-transferable")]
+    NonTransferable,
+    #[msg("Mint has a non-zero supply")]
+    MintHasSupply,
+    #[msg("Destination requires a memo on incoming transfers")]
+    NoMemo,
+    #[msg("Extension must be initialized in the transaction that initializes the mint")]
+    MintAlreadyInUse,
+    #[msg("Transfer hook accounts do not match its extra account metas")]
+    InvalidTransferHookAccounts,
 }
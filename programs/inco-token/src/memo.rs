@@ -2,10 +2,16 @@ use anchor_lang::prelude::*;
 use inco_lightning::cpi::accounts::Operation;
 use inco_lightning::cpi::new_euint128;
 use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::allowance::{allowance_address, call_allow_by_address};
+use crate::events::{MemoChunksEvent, MemoEvent};
+
+/// Plaintext bytes carried by one encrypted memo chunk (one euint128)
+pub const MEMO_CHUNK_LEN: usize = 16;
 
 /// Build encrypted memo - logs encrypted memo to transaction
 /// NOTE: Memo content must be convertible to u128 (max 16 bytes) since we use euint128 type
 /// This means memo can be max 16 characters for ASCII or represent a numeric value
+/// Emits a MemoEvent without recipients; see build_memo_with_recipients to grant access.
 pub fn build_memo(
     ctx: Context<BuildMemo>, 
     encrypted_memo: Vec<u8>,
@@ -23,7 +29,7 @@ pub fn build_memo(
             signer: ctx.accounts.authority.to_account_info(),
         }
     );
-    let _validated_memo = new_euint128(cpi_ctx, encrypted_memo.clone(), input_type)?;
+    let validated_memo = new_euint128(cpi_ctx, encrypted_memo.clone(), input_type)?;
 
     // Log encrypted memo data for TEE to capture and decrypt
     msg!("Encrypted memo (len {})", encrypted_memo.len());
@@ -31,6 +37,138 @@ pub fn build_memo(
     msg!("Input type: {}", input_type);
     msg!("Primary signer: {}", ctx.accounts.authority.key());
 
+    emit!(MemoEvent {
+        authority: ctx.accounts.authority.key(),
+        signers: ctx.remaining_accounts.iter().map(|signer| signer.key()).collect(),
+        recipients: vec![],
+        memo: validated_memo,
+    });
+
+    Ok(())
+}
+
+/// Build encrypted memo as build_memo does and grant each of `recipients` access to it
+/// remaining_accounts:
+///   [signer_1, ..., signer_n] - additional signers, all of which must sign
+///   [recipient_1, ..., recipient_m] - the recipients that are not signers
+///   allowance accounts, one per recipient, found by their derived addresses
+pub fn build_memo_with_recipients<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuildMemoWithRecipients<'info>>,
+    encrypted_memo: Vec<u8>,
+    input_type: u8,
+    recipients: Vec<Pubkey>,
+) -> Result<()> {
+    // IMPORTANT: This validates that the encrypted data represents a valid u128 value
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let authority = ctx.accounts.authority.to_account_info();
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: authority.clone() });
+    let memo = new_euint128(cpi_ctx, encrypted_memo.clone(), input_type)?;
+
+    // The signers run up to the first non-signing recipient or allowance account
+    let allowance_accounts: Vec<Pubkey> =
+        recipients.iter().map(|recipient| allowance_address(memo, recipient)).collect();
+    let signer_count = ctx
+        .remaining_accounts
+        .iter()
+        .position(|account| {
+            (recipients.contains(&account.key()) && !account.is_signer) || allowance_accounts.contains(&account.key())
+        })
+        .unwrap_or(ctx.remaining_accounts.len());
+    let signers = &ctx.remaining_accounts[..signer_count];
+    for account_info in signers {
+        require!(account_info.is_signer, IncoMemoError::MissingRequiredSignature);
+        msg!("Signed by {}", account_info.key());
+    }
+
+    let system_program = ctx.accounts.system_program.to_account_info();
+    for recipient in &recipients {
+        let recipient_info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|account| account.key() == *recipient)
+            .ok_or(IncoMemoError::MissingRecipient)?;
+        call_allow_by_address(&inco, &authority, &system_program, ctx.remaining_accounts, memo, recipient_info)?;
+    }
+
+    // Log encrypted memo data for TEE to capture and decrypt
+    msg!("Encrypted memo (len {})", encrypted_memo.len());
+    msg!("Encrypted memo data: {:?}", encrypted_memo);
+    msg!("Input type: {}", input_type);
+    msg!("Primary signer: {}", authority.key());
+
+    emit!(MemoEvent {
+        authority: authority.key(),
+        signers: signers.iter().map(|signer| signer.key()).collect(),
+        recipients,
+        memo,
+    });
+
+    Ok(())
+}
+
+/// Build a memo of any length from encrypted chunks of up to MEMO_CHUNK_LEN bytes each
+/// Every chunk is validated as its own handle and logged with its sequence number;
+/// the authority, the additional signers and `recipient` are granted each handle.
+/// remaining_accounts:
+///   [signer_1, ..., signer_n] - additional signers, all of which must sign
+///   [recipient] - when `recipient` is set and it is not a signer
+///   allowance accounts, one per chunk and reader, found by their derived addresses
+pub fn build_long_memo<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuildLongMemo<'info>>,
+    encrypted_chunks: Vec<Vec<u8>>,
+    input_type: u8,
+    recipient: Option<Pubkey>,
+) -> Result<()> {
+    require!(!encrypted_chunks.is_empty(), IncoMemoError::EmptyMemo);
+
+    let signer_count = ctx.remaining_accounts.iter().take_while(|account| account.is_signer).count();
+    let signers = &ctx.remaining_accounts[..signer_count];
+    for account_info in signers {
+        msg!("Signed by {}", account_info.key());
+    }
+
+    let authority = ctx.accounts.authority.to_account_info();
+    let mut readers = vec![&authority];
+    readers.extend(signers);
+    if let Some(recipient) = recipient {
+        let recipient_info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|account| account.key() == recipient)
+            .ok_or(IncoMemoError::MissingRecipient)?;
+        if !readers.iter().any(|reader| reader.key() == recipient) {
+            readers.push(recipient_info);
+        }
+    }
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let chunk_count = encrypted_chunks.len();
+    let mut chunks = Vec::with_capacity(chunk_count);
+
+    for (index, encrypted_chunk) in encrypted_chunks.into_iter().enumerate() {
+        msg!("Encrypted memo chunk {}/{} (len {})", index + 1, chunk_count, encrypted_chunk.len());
+        msg!("Encrypted memo chunk data: {:?}", encrypted_chunk);
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: authority.clone() });
+        let handle = new_euint128(cpi_ctx, encrypted_chunk, input_type)?;
+
+        for reader in &readers {
+            call_allow_by_address(&inco, &authority, &system_program, ctx.remaining_accounts, handle, reader)?;
+        }
+        chunks.push(handle);
+    }
+
+    msg!("Input type: {}", input_type);
+    msg!("Primary signer: {}", authority.key());
+
+    emit!(MemoChunksEvent {
+        authority: authority.key(),
+        signers: signers.iter().map(|signer| signer.key()).collect(),
+        recipient,
+        chunks,
+    });
+
     Ok(())
 }
 
@@ -45,6 +183,30 @@ pub struct BuildMemo<'info> {
     // remaining_accounts used for additional signers (like SPL memo)
 }
 
+#[derive(Accounts)]
+pub struct BuildMemoWithRecipients<'info> {
+    /// Pays for the allowance accounts of the recipients
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Inco Lightning program for encrypted operations
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: additional signers, the recipients and allowance accounts
+}
+
+#[derive(Accounts)]
+pub struct BuildLongMemo<'info> {
+    /// Pays for the allowance accounts of the chunks
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Inco Lightning program for encrypted operations
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    // remaining_accounts: additional signers, the recipient and allowance accounts
+}
+
 // ========== ERROR CODES ==========
 #[error_code]
 pub enum IncoMemoError {
@@ -52,4 +214,8 @@ pub enum IncoMemoError {
     MissingRequiredSignature,
     #[msg("Invalid inco_lightning program")]
     InvalidProgram,
+    #[msg("Memo has no chunks")]
+    EmptyMemo,
+    #[msg("Memo recipient accounts not provided")]
+    MissingRecipient,
 }
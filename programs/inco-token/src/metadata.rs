@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::events::*;
 
 // ========== INSTRUCTION ARGS ==========
 
@@ -152,6 +153,12 @@ pub fn create_metadata_account(
     metadata.collection_details = args.collection_details;
     metadata.is_initialized = true;
 
+    emit!(MetadataCreatedEvent {
+        metadata: metadata.key(),
+        mint: metadata.mint,
+        update_authority: metadata.update_authority,
+    });
+
     Ok(())
 }
 
@@ -216,6 +223,11 @@ pub fn update_metadata_account(
         metadata.uses = args.uses.to_option();
     }
 
+    emit!(MetadataUpdatedEvent {
+        metadata: metadata.key(),
+        update_authority: metadata.update_authority,
+    });
+
     Ok(())
 }
 
@@ -235,6 +247,12 @@ pub fn create_master_edition(
     edition.max_supply = args.max_supply;
     edition.is_initialized = true;
 
+    emit!(MasterEditionCreatedEvent {
+        edition: edition.key(),
+        metadata: metadata.key(),
+        max_supply: edition.max_supply,
+    });
+
     Ok(())
 }
 
@@ -265,6 +283,13 @@ pub fn print_edition(
     edition.edition = args.edition;
     edition.is_initialized = true;
 
+    emit!(EditionPrintedEvent {
+        edition: edition.key(),
+        master_edition: master_edition.key(),
+        edition_number: edition.edition,
+        supply: master_edition.supply,
+    });
+
     Ok(())
 }
 
@@ -272,6 +297,7 @@ pub fn print_edition(
 pub fn sign_metadata(ctx: Context<SignMetadata>) -> Result<()> {
     let metadata = &mut ctx.accounts.metadata;
     let creator = ctx.accounts.creator.key();
+    let metadata_key = metadata.key();
 
     require!(metadata.is_initialized, IncoMetadataError::NotInitialized);
 
@@ -279,6 +305,11 @@ pub fn sign_metadata(ctx: Context<SignMetadata>) -> Result<()> {
         for creator_entry in creators.iter_mut() {
             if creator_entry.address == creator {
                 creator_entry.verified = true;
+                emit!(CreatorVerificationEvent {
+                    metadata: metadata_key,
+                    creator,
+                    verified: true,
+                });
                 return Ok(());
             }
         }
@@ -291,6 +322,7 @@ pub fn sign_metadata(ctx: Context<SignMetadata>) -> Result<()> {
 pub fn remove_creator_verification(ctx: Context<RemoveCreatorVerification>) -> Result<()> {
     let metadata = &mut ctx.accounts.metadata;
     let creator = ctx.accounts.creator.key();
+    let metadata_key = metadata.key();
 
     require!(metadata.is_initialized, IncoMetadataError::NotInitialized);
 
@@ -298,6 +330,11 @@ pub fn remove_creator_verification(ctx: Context<RemoveCreatorVerification>) -> R
         for creator_entry in creators.iter_mut() {
             if creator_entry.address == creator {
                 creator_entry.verified = false;
+                emit!(CreatorVerificationEvent {
+                    metadata: metadata_key,
+                    creator,
+                    verified: false,
+                });
                 return Ok(());
             }
         }
@@ -321,17 +358,29 @@ pub fn set_and_verify_collection(
         key: collection.key,
     });
 
+    emit!(CollectionVerificationEvent {
+        metadata: metadata.key(),
+        collection: collection.key,
+        verified: true,
+    });
+
     Ok(())
 }
 
 /// Verify collection
 pub fn verify_collection(ctx: Context<VerifyCollection>) -> Result<()> {
     let metadata = &mut ctx.accounts.metadata;
+    let metadata_key = metadata.key();
 
     require!(metadata.is_initialized, IncoMetadataError::NotInitialized);
 
     if let Some(ref mut collection) = metadata.collection {
         collection.verified = true;
+        emit!(CollectionVerificationEvent {
+            metadata: metadata_key,
+            collection: collection.key,
+            verified: true,
+        });
     } else {
         return Err(IncoMetadataError::CollectionNotSet.into());
     }
@@ -342,11 +391,17 @@ pub fn verify_collection(ctx: Context<VerifyCollection>) -> Result<()> {
 /// Unverify collection
 pub fn unverify_collection(ctx: Context<UnverifyCollection>) -> Result<()> {
     let metadata = &mut ctx.accounts.metadata;
+    let metadata_key = metadata.key();
 
     require!(metadata.is_initialized, IncoMetadataError::NotInitialized);
 
     if let Some(ref mut collection) = metadata.collection {
         collection.verified = false;
+        emit!(CollectionVerificationEvent {
+            metadata: metadata_key,
+            collection: collection.key,
+            verified: false,
+        });
     } else {
         return Err(IncoMetadataError::CollectionNotSet.into());
     }
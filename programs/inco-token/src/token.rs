@@ -1,46 +1,194 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::pubkey::Pubkey;
-use inco_lightning::cpi::accounts::{Operation, Allow};
-use inco_lightning::cpi::{e_add, e_ge, e_select, e_sub, new_euint128, as_euint128, allow};
+use inco_lightning::cpi::accounts::{Operation, VerifySignature};
+use inco_lightning::cpi::{e_add, e_ge, e_select, e_sub, new_euint128, as_euint128, is_validsignature};
 use inco_lightning::types::Euint128;
 use inco_lightning::ID as INCO_LIGHTNING_ID;
-pub use crate::{AccountState, COption, CustomError, IncoMint, IncoAccount};
+use crate::allowance::{allow_auditor, call_allow_from_remaining};
+use crate::extension::grow_account;
+use crate::token_2022::{
+    default_account_state, init_account_extensions, invoke_transfer_hook, permanent_delegate, require_no_transfer_fee, require_plain_transfers,
+    require_transfer_memo, require_transferable, require_transferable_account, split_extension_accounts, withheld_amount,
+};
+pub use crate::{AccountState, AuthorityType, COption, CustomError, IncoMint, AmountInput, IncoAccount, IncoMultisig, MAX_SIGNERS, NATIVE_MINT_SEED};
+use crate::events::*;
+
+// ========== HELPER FUNCTIONS ==========
+
+/// Validate that `authority` acts for `expected_authority`
+/// A single-key authority must sign the transaction. An IncoMultisig authority
+/// instead needs at least `m` of its signers as the leading signer accounts of
+/// remaining_accounts. Returns how many remaining_accounts those signers take up.
+pub fn validate_owner<'info>(
+    expected_authority: &Pubkey,
+    authority: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<usize> {
+    require!(*expected_authority == authority.key(), CustomError::OwnerMismatch);
+
+    if authority.owner == &crate::ID {
+        let data = authority.try_borrow_data()?;
+        if let Ok(multisig) = IncoMultisig::try_deserialize(&mut &data[..]) {
+            require!(multisig.is_initialized, CustomError::UninitializedState);
+
+            let mut matched = [false; MAX_SIGNERS];
+            let mut num_signers: u8 = 0;
+            let mut consumed = 0;
+            for signer in remaining_accounts.iter() {
+                if !signer.is_signer {
+                    break;
+                }
+                let position = multisig.signers[..multisig.n as usize]
+                    .iter()
+                    .position(|key| key == signer.key);
+                match position {
+                    Some(index) if !matched[index] => {
+                        matched[index] = true;
+                        num_signers += 1;
+                    }
+                    Some(_) => {}
+                    None => break,
+                }
+                consumed += 1;
+            }
+            require!(num_signers >= multisig.m, CustomError::InvalidNumberOfProvidedSigners);
+
+            return Ok(consumed);
+        }
+    }
 
-// ========== HELPER FUNCTION ==========
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature.into());
+    }
+    Ok(0)
+}
 
-/// Helper to call allow with accounts from remaining_accounts
-/// remaining_accounts[offset] = allowance_account (mut)
-/// remaining_accounts[offset+1] = allowed_address (readonly)
-fn call_allow_from_remaining<'info>(
+/// Signer for Inco Lightning CPIs: the authority itself, or the first
+/// multisig signer when validate_owner consumed multisig signers
+pub fn operation_signer<'info>(
+    authority: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    multisig_signers: usize,
+) -> AccountInfo<'info> {
+    if multisig_signers > 0 {
+        remaining_accounts[0].clone()
+    } else {
+        authority.clone()
+    }
+}
+
+/// Verify an Inco attested decryption of `handle` and return the plaintext
+/// `plaintext` is the little-endian u128 the attestation was issued for.
+/// The Ed25519 attestation instruction must precede this one in the transaction
+pub fn verify_attested_plaintext<'info>(
     inco_program: &AccountInfo<'info>,
+    instructions: &AccountInfo<'info>,
     signer: &AccountInfo<'info>,
-    system_program: &AccountInfo<'info>,
-    remaining_accounts: &[AccountInfo<'info>],
     handle: Euint128,
-    allowed_pubkey: Pubkey,
-    account_offset: usize,
+    plaintext: Vec<u8>,
+) -> Result<u128> {
+    let value = <[u8; 16]>::try_from(plaintext.as_slice())
+        .map_err(|_| CustomError::InvalidInstruction)?;
+
+    let cpi_ctx = CpiContext::new(
+        inco_program.clone(),
+        VerifySignature {
+            instructions: instructions.clone(),
+            signer: signer.clone(),
+        }
+    );
+    is_validsignature(
+        cpi_ctx,
+        1,
+        Some(vec![handle.0.to_le_bytes().to_vec()]),
+        Some(vec![plaintext]),
+    )?;
+    Ok(u128::from_le_bytes(value))
+}
+
+/// Verify Inco attested decryptions showing every one of `handles` decrypts to zero
+/// `plaintext` is the shared little-endian zero the attestations were issued for
+pub fn verify_zero_handles<'info>(
+    inco_program: &AccountInfo<'info>,
+    instructions: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    handles: &[Euint128],
+    plaintext: Vec<u8>,
 ) -> Result<()> {
-    if remaining_accounts.len() < account_offset + 2 {
-        return Err(CustomError::InvalidInstruction.into());
-    }
-    
-    let allowance_account = &remaining_accounts[account_offset];
-    let allowed_address = &remaining_accounts[account_offset + 1];
-    
+    let value = <[u8; 16]>::try_from(plaintext.as_slice())
+        .map_err(|_| CustomError::InvalidInstruction)?;
+    require!(u128::from_le_bytes(value) == 0, CustomError::NonNativeHasBalance);
+
     let cpi_ctx = CpiContext::new(
         inco_program.clone(),
-        Allow {
-            allowance_account: allowance_account.clone(),
+        VerifySignature {
+            instructions: instructions.clone(),
             signer: signer.clone(),
-            allowed_address: allowed_address.clone(),
-            system_program: system_program.clone(),
         }
     );
-    
-    allow(cpi_ctx, handle.0, true, allowed_pubkey)?;
+    is_validsignature(
+        cpi_ctx,
+        handles.len() as u8,
+        Some(handles.iter().map(|handle| handle.0.to_le_bytes().to_vec()).collect()),
+        Some(vec![plaintext; handles.len()]),
+    )?;
     Ok(())
 }
 
+/// Whether `mint` is the native (wrapped SOL) mint PDA
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    Pubkey::find_program_address(&[NATIVE_MINT_SEED], &crate::ID).0 == *mint
+}
+
+/// `is_native` value for a new account: the rent-exempt reserve for native mint
+/// accounts, None otherwise
+pub fn native_reserve(mint: &Pubkey, account: &AccountInfo) -> Result<COption<u64>> {
+    if is_native_mint(mint) {
+        Ok(COption::Some(Rent::get()?.minimum_balance(account.data_len())))
+    } else {
+        Ok(COption::None)
+    }
+}
+
+/// Amount actually minted: `amount` when supply + amount stays within the
+/// mint's max supply, zero otherwise. Uncapped mints mint `amount` as is
+pub fn capped_mint_amount<'info>(
+    inco_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    mint: &IncoMint,
+    amount: Euint128,
+) -> Result<Euint128> {
+    let max_supply = match mint.max_supply {
+        COption::Some(max_supply) => max_supply,
+        COption::None => return Ok(amount),
+    };
+
+    let cpi_ctx = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let proposed_supply = e_add(cpi_ctx, mint.supply, amount, 0u8)?;
+
+    let cpi_ctx2 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let within_cap = e_ge(cpi_ctx2, max_supply, proposed_supply, 0u8)?;
+
+    let cpi_ctx3 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let zero_value = as_euint128(cpi_ctx3, 0)?;
+
+    let cpi_ctx4 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    e_select(cpi_ctx4, within_cap, amount, zero_value, 0u8)
+}
+
+/// Handle of a plaintext or encrypted amount
+pub fn amount_input_handle<'info>(
+    inco_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    input: AmountInput,
+) -> Result<Euint128> {
+    let cpi_ctx = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    match input {
+        AmountInput::Plaintext(value) => as_euint128(cpi_ctx, value),
+        AmountInput::Encrypted { ciphertext, input_type } => new_euint128(cpi_ctx, ciphertext, input_type),
+    }
+}
+
 // ========== TOKEN INSTRUCTIONS ==========
 
 pub fn initialize_mint(
@@ -70,6 +218,47 @@ pub fn initialize_mint(
         Some(authority) => COption::Some(authority),
         None => COption::None,
     };
+    mint.auditor = COption::None;
+    mint.max_supply = COption::None;
+
+    emit!(InitializeMintEvent {
+        mint: mint.key(),
+        mint_authority: mint.mint_authority.to_option(),
+        freeze_authority: mint.freeze_authority.to_option(),
+        decimals,
+        supply: mint.supply,
+    });
+
+    Ok(())
+}
+
+/// Initialize an M-of-N multisig authority
+/// remaining_accounts:
+///   [0..n] signer addresses (readonly)
+pub fn initialize_multisig<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeMultisig<'info>>,
+    m: u8
+) -> Result<()> {
+    let multisig = &mut ctx.accounts.multisig;
+
+    require!(!multisig.is_initialized, CustomError::AlreadyInUse);
+
+    let n = ctx.remaining_accounts.len();
+    require!((1..=MAX_SIGNERS).contains(&n), CustomError::InvalidNumberOfProvidedSigners);
+    require!(m >= 1 && m as usize <= n, CustomError::InvalidNumberOfRequiredSigners);
+
+    for (index, signer) in ctx.remaining_accounts.iter().enumerate() {
+        multisig.signers[index] = signer.key();
+    }
+    multisig.m = m;
+    multisig.n = n as u8;
+    multisig.is_initialized = true;
+
+    emit!(InitializeMultisigEvent {
+        multisig: multisig.key(),
+        m,
+        n: multisig.n,
+    });
 
     Ok(())
 }
@@ -92,20 +281,35 @@ pub fn initialize_account(ctx: Context<InitializeAccount>) -> Result<()> {
 
     account.amount = zero_amount;
     account.delegate = COption::None;
-    account.state = AccountState::Initialized;
-    account.is_native = COption::None;
+    account.state = default_account_state(&mint.to_account_info())?;
+    account.is_native = native_reserve(&mint.key(), &account.to_account_info())?;
 
-    let cpi_ctx2 = CpiContext::new(inco, Operation { signer });
+    let cpi_ctx2 = CpiContext::new(inco, Operation { signer: signer.clone() });
     let zero_delegated = as_euint128(cpi_ctx2, 0)?;
 
     account.delegated_amount = zero_delegated;
     account.close_authority = COption::None;
 
+    init_account_extensions(
+        &account.to_account_info(),
+        &mint.to_account_info(),
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        zero_delegated,
+    )?;
+
+    emit!(InitializeAccountEvent {
+        account: account.key(),
+        mint: account.mint,
+        owner: account.owner,
+        amount: account.amount,
+    });
+
     Ok(())
 }
 
 /// Mint tokens to an account
-/// remaining_accounts:
+/// remaining_accounts (after any multisig signers):
 ///   [0] allowance_account (mut) - PDA derived from [new_balance_handle, owner]
 ///   [1] owner_address (readonly) - The owner to grant access to
 pub fn mint_to<'info>(
@@ -124,13 +328,15 @@ pub fn mint_to<'info>(
         COption::Some(authority) => authority,
         COption::None => return Err(CustomError::FixedSupply.into()),
     };
-    require!(mint_authority == ctx.accounts.mint_authority.key(), CustomError::OwnerMismatch);
+    let multisig_signers = validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
 
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
-    let signer = ctx.accounts.mint_authority.to_account_info();
+    let signer = operation_signer(&ctx.accounts.mint_authority, ctx.remaining_accounts, multisig_signers);
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
+    let requested = new_euint128(cpi_ctx, ciphertext, input_type)?;
+    let amount = capped_mint_amount(&inco, &signer, mint, requested)?;
 
     let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let new_supply = e_add(cpi_ctx2, mint.supply, amount, 0u8)?;
@@ -140,28 +346,49 @@ pub fn mint_to<'info>(
     let new_balance = e_add(cpi_ctx3, account.amount, amount, 0u8)?;
     account.amount = new_balance;
 
-    // Grant allowance to owner if remaining_accounts provided
-    if ctx.remaining_accounts.len() >= 2 {
-        call_allow_from_remaining(
-            &inco,
-            &signer,
-            &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            new_balance,
-            account.owner,
-            0,
-        )?;
-    }
+    // Grant allowance to owner
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_balance,
+        account.owner,
+        0,
+    )?;
+
+    allow_auditor(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint,
+        &[amount, new_balance, new_supply],
+    )?;
+
+    emit!(MintToEvent {
+        mint: mint.key(),
+        account: account.key(),
+        amount,
+        new_balance,
+        new_supply,
+    });
 
     Ok(())
 }
 
 /// Transfer tokens between accounts
-/// remaining_accounts:
+/// remaining_accounts (after any multisig signers):
 ///   [0] source_allowance_account (mut)
 ///   [1] source_owner_address (readonly)
 ///   [2] dest_allowance_account (mut)
 ///   [3] dest_owner_address (readonly)
+///   [4] delegated_allowance_account (mut) - only when a delegate signs
+///   [5] source_owner_address (readonly)
+///   [6] delegated_allowance_account (mut) - only when a delegate signs
+///   [7] delegate_address (readonly)
+///   then the instructions sysvar when the destination requires memos
+///   then the transfer hook accounts when the mint has a hook (see invoke_transfer_hook)
 pub fn transfer<'info>(
     ctx: Context<'_, '_, '_, 'info, IncoTransfer<'info>>,
     ciphertext: Vec<u8>,
@@ -175,6 +402,8 @@ pub fn transfer<'info>(
     require!(source.state != AccountState::Frozen, CustomError::AccountFrozen);
     require!(destination.state != AccountState::Frozen, CustomError::AccountFrozen);
     require!(source.mint == destination.mint, CustomError::MintMismatch);
+    require_no_transfer_fee(&ctx.accounts.mint.to_account_info())?;
+    require_transferable(&ctx.accounts.mint.to_account_info())?;
 
     // Early return for self-transfer
     if source.key() == destination.key() {
@@ -183,15 +412,22 @@ pub fn transfer<'info>(
 
     // Check ownership/delegation
     let authority_key = ctx.accounts.authority.key();
-    if source.owner != authority_key {
+    // The mint's permanent delegate acts with the owner's rights
+    let is_permanent_delegate = permanent_delegate(&ctx.accounts.mint.to_account_info())? == Some(authority_key);
+    let is_delegate = source.owner != authority_key && !is_permanent_delegate;
+    if is_delegate {
         match source.delegate {
             COption::Some(delegate) if delegate == authority_key => {}
             _ => return Err(CustomError::OwnerMismatch.into()),
         }
     }
+    let multisig_signers = validate_owner(&authority_key, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    let (remaining_accounts, extension_accounts) =
+        split_extension_accounts(&ctx.accounts.mint.to_account_info(), &ctx.remaining_accounts[multisig_signers..])?;
+    require_transfer_memo(&destination.to_account_info(), extension_accounts)?;
 
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
-    let signer = ctx.accounts.authority.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
@@ -203,7 +439,19 @@ pub fn transfer<'info>(
     let zero_value = as_euint128(cpi_ctx3, 0)?;
 
     let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let transfer_amount = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+    let mut transfer_amount = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+
+    // A delegate can only move up to its remaining allowance
+    if is_delegate {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let within_allowance = e_ge(cpi_ctx, source.delegated_amount, transfer_amount, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        transfer_amount = e_select(cpi_ctx, within_allowance, transfer_amount, zero_value, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        source.delegated_amount = e_sub(cpi_ctx, source.delegated_amount, transfer_amount, 0u8)?;
+    }
 
     let cpi_ctx5 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let new_source_balance = e_sub(cpi_ctx5, source.amount, transfer_amount, 0u8)?;
@@ -214,36 +462,253 @@ pub fn transfer<'info>(
     destination.amount = new_dest_balance;
 
     // Grant allowance to source owner
-    if ctx.remaining_accounts.len() >= 2 {
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_source_balance,
+        source.owner,
+        0,
+    )?;
+
+    // Grant allowance to destination owner
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_dest_balance,
+        destination.owner,
+        2,
+    )?;
+
+    // Grant remaining allowance to source owner and delegate
+    if is_delegate {
         call_allow_from_remaining(
             &inco,
             &signer,
             &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            new_source_balance,
+            remaining_accounts,
+            source.delegated_amount,
             source.owner,
-            0,
+            4,
+        )?;
+        call_allow_from_remaining(
+            &inco,
+            &signer,
+            &ctx.accounts.system_program.to_account_info(),
+            remaining_accounts,
+            source.delegated_amount,
+            authority_key,
+            6,
         )?;
     }
 
-    // Grant allowance to destination owner
-    if ctx.remaining_accounts.len() >= 4 {
+    let mut audited_handles = vec![transfer_amount, new_source_balance, new_dest_balance];
+    if is_delegate {
+        audited_handles.push(source.delegated_amount);
+    }
+    allow_auditor(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        &ctx.accounts.mint,
+        &audited_handles,
+    )?;
+
+    invoke_transfer_hook(
+        &source.to_account_info(),
+        &ctx.accounts.mint.to_account_info(),
+        &destination.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        extension_accounts,
+        transfer_amount,
+    )?;
+
+    emit!(TransferEvent {
+        source: source.key(),
+        destination: destination.key(),
+        authority: authority_key,
+        amount: transfer_amount,
+        new_source_balance,
+        new_destination_balance: new_dest_balance,
+        delegated_amount: source.delegated_amount,
+    });
+
+    Ok(())
+}
+
+/// Transfer from one source to many destinations in a single instruction
+/// Legs are checked against the running source balance, so a leg that would
+/// overdraw it transfers zero instead while the other legs still settle
+/// remaining_accounts (after any multisig signers), with n = ciphertexts.len():
+///   [0..n] destination accounts (mut), one per ciphertext
+///   [n] source_allowance_account (mut)
+///   [n+1] source_owner_address (readonly)
+///   [n+2+2i] dest_allowance_account for destination i (mut)
+///   [n+3+2i] dest_owner_address for destination i (readonly)
+///   then, when a delegate signs: [owner_delegated_allowance, source_owner, delegate_delegated_allowance, delegate]
+pub fn transfer_batch<'info>(
+    ctx: Context<'_, '_, '_, 'info, IncoTransferBatch<'info>>,
+    ciphertexts: Vec<Vec<u8>>,
+    input_type: u8
+) -> Result<()> {
+    let source = &mut ctx.accounts.source;
+    let leg_count = ciphertexts.len();
+
+    require!(source.state == AccountState::Initialized, CustomError::UninitializedState);
+    require!(source.state != AccountState::Frozen, CustomError::AccountFrozen);
+    require!(leg_count > 0, CustomError::InvalidInstruction);
+    require_plain_transfers(&ctx.accounts.mint.to_account_info())?;
+
+    let authority_key = ctx.accounts.authority.key();
+    // The mint's permanent delegate acts with the owner's rights, as in transfer
+    let is_permanent_delegate = permanent_delegate(&ctx.accounts.mint.to_account_info())? == Some(authority_key);
+    let is_delegate = source.owner != authority_key && !is_permanent_delegate;
+    if is_delegate {
+        match source.delegate {
+            COption::Some(delegate) if delegate == authority_key => {}
+            _ => return Err(CustomError::OwnerMismatch.into()),
+        }
+    }
+    let multisig_signers = validate_owner(&authority_key, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+    require!(remaining_accounts.len() >= leg_count, CustomError::InvalidInstruction);
+    let (destinations, allowance_accounts) = remaining_accounts.split_at(leg_count);
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero_value = as_euint128(cpi_ctx, 0)?;
+
+    let mut audited_handles = Vec::with_capacity(2 * leg_count + 2);
+
+    for (index, (destination_info, ciphertext)) in destinations.iter().zip(ciphertexts).enumerate() {
+        require!(destination_info.owner == &crate::ID, CustomError::InvalidInstruction);
+        require!(destination_info.is_writable, CustomError::InvalidInstruction);
+
+        // Self-transfer legs are a no-op, as in transfer
+        if destination_info.key() == source.key() {
+            continue;
+        }
+
+        let mut destination = IncoAccount::try_deserialize(&mut &destination_info.try_borrow_data()?[..])?;
+        require!(destination.state == AccountState::Initialized, CustomError::UninitializedState);
+        require!(destination.state != AccountState::Frozen, CustomError::AccountFrozen);
+        require!(destination.mint == source.mint, CustomError::MintMismatch);
+        // A batch has no room for a memo per leg
+        require_transfer_memo(destination_info, &[])?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
+
+        let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let has_sufficient = e_ge(cpi_ctx2, source.amount, amount, 0u8)?;
+
+        let cpi_ctx3 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let mut transfer_amount = e_select(cpi_ctx3, has_sufficient, amount, zero_value, 0u8)?;
+
+        if is_delegate {
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            let within_allowance = e_ge(cpi_ctx, source.delegated_amount, transfer_amount, 0u8)?;
+
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            transfer_amount = e_select(cpi_ctx, within_allowance, transfer_amount, zero_value, 0u8)?;
+
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            source.delegated_amount = e_sub(cpi_ctx, source.delegated_amount, transfer_amount, 0u8)?;
+        }
+
+        let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let new_source_balance = e_sub(cpi_ctx4, source.amount, transfer_amount, 0u8)?;
+        source.amount = new_source_balance;
+
+        let cpi_ctx5 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let new_dest_balance = e_add(cpi_ctx5, destination.amount, transfer_amount, 0u8)?;
+        destination.amount = new_dest_balance;
+
+        destination.try_serialize(&mut &mut destination_info.try_borrow_mut_data()?[..])?;
+        audited_handles.push(transfer_amount);
+        audited_handles.push(new_dest_balance);
+
+        // Grant allowance to destination owner
         call_allow_from_remaining(
             &inco,
             &signer,
-            &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
+            &system_program,
+            allowance_accounts,
             new_dest_balance,
             destination.owner,
-            2,
+            2 + 2 * index,
         )?;
+
+        emit!(TransferEvent {
+            source: source.key(),
+            destination: destination_info.key(),
+            authority: authority_key,
+            amount: transfer_amount,
+            new_source_balance,
+            new_destination_balance: new_dest_balance,
+            delegated_amount: source.delegated_amount,
+        });
     }
 
+    // Grant allowance to source owner for the final balance
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &system_program,
+        allowance_accounts,
+        source.amount,
+        source.owner,
+        0,
+    )?;
+
+    // Grant remaining allowance to source owner and delegate
+    let delegated_offset = 2 + 2 * leg_count;
+    if is_delegate {
+        call_allow_from_remaining(
+            &inco,
+            &signer,
+            &system_program,
+            allowance_accounts,
+            source.delegated_amount,
+            source.owner,
+            delegated_offset,
+        )?;
+        call_allow_from_remaining(
+            &inco,
+            &signer,
+            &system_program,
+            allowance_accounts,
+            source.delegated_amount,
+            authority_key,
+            delegated_offset + 2,
+        )?;
+    }
+
+    audited_handles.push(source.amount);
+    if is_delegate {
+        audited_handles.push(source.delegated_amount);
+    }
+    allow_auditor(
+        &inco,
+        &signer,
+        &system_program,
+        allowance_accounts,
+        &ctx.accounts.mint,
+        &audited_handles,
+    )?;
+
     Ok(())
 }
 
 /// Approve a delegate
-/// remaining_accounts:
+/// remaining_accounts (after any multisig signers):
 ///   [0] allowance_account (mut)
 ///   [1] delegate_address (readonly)
 pub fn approve<'info>(
@@ -256,60 +721,82 @@ pub fn approve<'info>(
     require!(source.state == AccountState::Initialized, CustomError::UninitializedState);
     require!(source.state != AccountState::Frozen, CustomError::AccountFrozen);
     require!(source.owner == ctx.accounts.owner.key(), CustomError::OwnerMismatch);
+    require_transferable(&ctx.accounts.mint.to_account_info())?;
+    let multisig_signers = validate_owner(&source.owner, &ctx.accounts.owner, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
 
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.owner, ctx.remaining_accounts, multisig_signers);
 
-    let cpi_ctx = CpiContext::new(
-        inco.clone(),
-        Operation {
-            signer: ctx.accounts.owner.to_account_info(),
-        }
-    );
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
 
     source.delegate = COption::Some(ctx.accounts.delegate.key());
     source.delegated_amount = amount;
 
     // Grant allowance to delegate
-    if ctx.remaining_accounts.len() >= 2 {
-        call_allow_from_remaining(
-            &inco,
-            &ctx.accounts.owner.to_account_info(),
-            &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            amount,
-            ctx.accounts.delegate.key(),
-            0,
-        )?;
-    }
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        amount,
+        ctx.accounts.delegate.key(),
+        0,
+    )?;
+
+    allow_auditor(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        &ctx.accounts.mint,
+        &[amount],
+    )?;
+
+    emit!(ApproveEvent {
+        source: source.key(),
+        delegate: ctx.accounts.delegate.key(),
+        delegated_amount: amount,
+    });
 
     Ok(())
 }
 
-pub fn revoke(ctx: Context<IncoRevoke>) -> Result<()> {
+pub fn revoke<'info>(ctx: Context<'_, '_, '_, 'info, IncoRevoke<'info>>) -> Result<()> {
     let source = &mut ctx.accounts.source;
 
     require!(source.state == AccountState::Initialized, CustomError::UninitializedState);
     require!(source.owner == ctx.accounts.owner.key(), CustomError::OwnerMismatch);
+    let multisig_signers = validate_owner(&source.owner, &ctx.accounts.owner, ctx.remaining_accounts)?;
 
     source.delegate = COption::None;
 
     let cpi_ctx = CpiContext::new(
         ctx.accounts.inco_lightning_program.to_account_info(),
         Operation {
-            signer: ctx.accounts.owner.to_account_info(),
+            signer: operation_signer(&ctx.accounts.owner, ctx.remaining_accounts, multisig_signers),
         }
     );
     let zero_delegated = as_euint128(cpi_ctx, 0)?;
     source.delegated_amount = zero_delegated;
 
+    emit!(RevokeEvent {
+        source: source.key(),
+        delegated_amount: zero_delegated,
+    });
+
     Ok(())
 }
 
 /// Burn tokens
-/// remaining_accounts:
+/// remaining_accounts (after any multisig signers):
 ///   [0] allowance_account (mut)
 ///   [1] owner_address (readonly)
+///   [2] delegated_allowance_account (mut) - only when a delegate signs
+///   [3] owner_address (readonly)
+///   [4] delegated_allowance_account (mut) - only when a delegate signs
+///   [5] delegate_address (readonly)
 pub fn burn<'info>(
     ctx: Context<'_, '_, '_, 'info, IncoBurn<'info>>,
     ciphertext: Vec<u8>,
@@ -322,17 +809,23 @@ pub fn burn<'info>(
     require!(account.state != AccountState::Frozen, CustomError::AccountFrozen);
     require!(mint.is_initialized, CustomError::UninitializedState);
     require!(account.mint == mint.key(), CustomError::MintMismatch);
+    require!(account.is_native.is_none(), CustomError::NativeNotSupported);
 
     let authority_key = ctx.accounts.authority.key();
-    if account.owner != authority_key {
+    // The mint's permanent delegate acts with the owner's rights
+    let is_permanent_delegate = permanent_delegate(&mint.to_account_info())? == Some(authority_key);
+    let is_delegate = account.owner != authority_key && !is_permanent_delegate;
+    if is_delegate {
         match account.delegate {
             COption::Some(delegate) if delegate == authority_key => {}
             _ => return Err(CustomError::OwnerMismatch.into()),
         }
     }
+    let multisig_signers = validate_owner(&authority_key, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
 
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
-    let signer = ctx.accounts.authority.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
@@ -344,7 +837,19 @@ pub fn burn<'info>(
     let zero_value = as_euint128(cpi_ctx3, 0)?;
 
     let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let burn_amount = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+    let mut burn_amount = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+
+    // A delegate can only burn up to its remaining allowance
+    if is_delegate {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let within_allowance = e_ge(cpi_ctx, account.delegated_amount, burn_amount, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        burn_amount = e_select(cpi_ctx, within_allowance, burn_amount, zero_value, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        account.delegated_amount = e_sub(cpi_ctx, account.delegated_amount, burn_amount, 0u8)?;
+    }
 
     let cpi_ctx5 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let new_balance = e_sub(cpi_ctx5, account.amount, burn_amount, 0u8)?;
@@ -355,22 +860,65 @@ pub fn burn<'info>(
     mint.supply = new_supply;
 
     // Grant allowance to owner
-    if ctx.remaining_accounts.len() >= 2 {
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_balance,
+        account.owner,
+        0,
+    )?;
+
+    // Grant remaining allowance to owner and delegate
+    if is_delegate {
         call_allow_from_remaining(
             &inco,
             &signer,
             &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            new_balance,
+            remaining_accounts,
+            account.delegated_amount,
             account.owner,
-            0,
+            2,
+        )?;
+        call_allow_from_remaining(
+            &inco,
+            &signer,
+            &ctx.accounts.system_program.to_account_info(),
+            remaining_accounts,
+            account.delegated_amount,
+            authority_key,
+            4,
         )?;
     }
 
+    let mut audited_handles = vec![burn_amount, new_balance, new_supply];
+    if is_delegate {
+        audited_handles.push(account.delegated_amount);
+    }
+    allow_auditor(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint,
+        &audited_handles,
+    )?;
+
+    emit!(BurnEvent {
+        account: account.key(),
+        mint: mint.key(),
+        authority: authority_key,
+        amount: burn_amount,
+        new_balance,
+        new_supply,
+        delegated_amount: account.delegated_amount,
+    });
+
     Ok(())
 }
 
-pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
+pub fn freeze_account<'info>(ctx: Context<'_, '_, '_, 'info, FreezeAccount<'info>>) -> Result<()> {
     let account = &mut ctx.accounts.account;
     let mint = &ctx.accounts.mint;
 
@@ -382,13 +930,18 @@ pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
         COption::Some(authority) => authority,
         COption::None => return Err(CustomError::MintCannotFreeze.into()),
     };
-    require!(freeze_authority == ctx.accounts.freeze_authority.key(), CustomError::OwnerMismatch);
+    validate_owner(&freeze_authority, &ctx.accounts.freeze_authority, ctx.remaining_accounts)?;
 
     account.state = AccountState::Frozen;
+    emit!(FreezeEvent {
+        account: account.key(),
+        mint: mint.key(),
+    });
+
     Ok(())
 }
 
-pub fn thaw_account(ctx: Context<ThawAccount>) -> Result<()> {
+pub fn thaw_account<'info>(ctx: Context<'_, '_, '_, 'info, ThawAccount<'info>>) -> Result<()> {
     let account = &mut ctx.accounts.account;
     let mint = &ctx.accounts.mint;
 
@@ -400,13 +953,26 @@ pub fn thaw_account(ctx: Context<ThawAccount>) -> Result<()> {
         COption::Some(authority) => authority,
         COption::None => return Err(CustomError::MintCannotFreeze.into()),
     };
-    require!(freeze_authority == ctx.accounts.freeze_authority.key(), CustomError::OwnerMismatch);
+    validate_owner(&freeze_authority, &ctx.accounts.freeze_authority, ctx.remaining_accounts)?;
 
     account.state = AccountState::Initialized;
+    emit!(ThawEvent {
+        account: account.key(),
+        mint: mint.key(),
+    });
+
     Ok(())
 }
 
-pub fn close_account(ctx: Context<CloseAccount>) -> Result<()> {
+/// Close an account whose encrypted balance is attested to be zero
+/// `balance_plaintext` is the attested plaintext of `account.amount`, which
+/// must be zero; the data is wiped and the account handed back to the system program.
+/// An account holding a TransferFeeAmount must also have its withheld fees
+/// attested to be zero, so they are harvested before the account goes away.
+pub fn close_account<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseAccount<'info>>,
+    balance_plaintext: Vec<u8>
+) -> Result<()> {
     let account = &ctx.accounts.account;
 
     require!(account.state == AccountState::Initialized, CustomError::UninitializedState);
@@ -418,18 +984,28 @@ pub fn close_account(ctx: Context<CloseAccount>) -> Result<()> {
         COption::None => false,
     };
     require!(is_owner || is_close_authority, CustomError::OwnerMismatch);
-
-    // Transfer remaining lamports to destination
-    let dest_starting_lamports = ctx.accounts.destination.lamports();
-    **ctx.accounts.destination.lamports.borrow_mut() = dest_starting_lamports
-        .checked_add(ctx.accounts.account.to_account_info().lamports())
-        .ok_or(CustomError::Overflow)?;
-    **ctx.accounts.account.to_account_info().lamports.borrow_mut() = 0;
+    let multisig_signers = validate_owner(&authority_key, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+    let mut handles = vec![account.amount];
+    handles.extend(withheld_amount(&account.to_account_info())?);
+    verify_zero_handles(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.instructions,
+        &operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers),
+        &handles,
+        balance_plaintext,
+    )?;
+
+    // Lamports, data and ownership are released by the `close` constraint
+    emit!(CloseEvent {
+        account: account.key(),
+        destination: ctx.accounts.destination.key(),
+    });
 
     Ok(())
 }
 
-pub fn set_mint_authority(ctx: Context<SetMintAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+pub fn set_mint_authority<'info>(ctx: Context<'_, '_, '_, 'info, SetMintAuthority<'info>>, new_authority: Option<Pubkey>) -> Result<()> {
     let mint = &mut ctx.accounts.mint;
     require!(mint.is_initialized, CustomError::UninitializedState);
 
@@ -437,17 +1013,23 @@ pub fn set_mint_authority(ctx: Context<SetMintAuthority>, new_authority: Option<
         COption::Some(authority) => authority,
         COption::None => return Err(CustomError::FixedSupply.into()),
     };
-    require!(current_authority == ctx.accounts.current_authority.key(), CustomError::OwnerMismatch);
+    validate_owner(&current_authority, &ctx.accounts.current_authority, ctx.remaining_accounts)?;
 
     mint.mint_authority = match new_authority {
         Some(authority) => COption::Some(authority),
         None => COption::None,
     };
 
+    emit!(AuthorityChangedEvent {
+        owned: mint.key(),
+        authority_type: AuthorityType::MintTokens,
+        new_authority,
+    });
+
     Ok(())
 }
 
-pub fn set_freeze_authority(ctx: Context<SetFreezeAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+pub fn set_freeze_authority<'info>(ctx: Context<'_, '_, '_, 'info, SetFreezeAuthority<'info>>, new_authority: Option<Pubkey>) -> Result<()> {
     let mint = &mut ctx.accounts.mint;
     require!(mint.is_initialized, CustomError::UninitializedState);
 
@@ -455,35 +1037,413 @@ pub fn set_freeze_authority(ctx: Context<SetFreezeAuthority>, new_authority: Opt
         COption::Some(authority) => authority,
         COption::None => return Err(CustomError::MintCannotFreeze.into()),
     };
-    require!(current_authority == ctx.accounts.current_authority.key(), CustomError::OwnerMismatch);
+    validate_owner(&current_authority, &ctx.accounts.current_authority, ctx.remaining_accounts)?;
 
     mint.freeze_authority = match new_authority {
         Some(authority) => COption::Some(authority),
         None => COption::None,
     };
 
+    emit!(AuthorityChangedEvent {
+        owned: mint.key(),
+        authority_type: AuthorityType::FreezeAccount,
+        new_authority,
+    });
+
     Ok(())
 }
 
-pub fn set_account_owner(ctx: Context<SetAccountOwner>, new_owner: Pubkey) -> Result<()> {
+pub fn set_auditor<'info>(ctx: Context<'_, '_, '_, 'info, SetAuditor<'info>>, auditor: Option<Pubkey>) -> Result<()> {
+    let mint = &mut ctx.accounts.mint;
+    require!(mint.is_initialized, CustomError::UninitializedState);
+
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+
+    mint.auditor = match auditor {
+        Some(auditor) => COption::Some(auditor),
+        None => COption::None,
+    };
+
+    emit!(AuditorChangedEvent {
+        mint: mint.key(),
+        auditor,
+    });
+
+    Ok(())
+}
+
+/// Grow a mint created before the auditor or max_supply fields to the current
+/// IncoMint size. Older mints still deserialize, as their missing fields read as
+/// zeroed padding, but may have no room to store them. The new space starts
+/// zeroed, meaning no auditor and no cap; anyone may pay for the migration.
+pub fn migrate_mint<'info>(ctx: Context<'_, '_, '_, 'info, MigrateMint<'info>>) -> Result<()> {
+    let mint = ctx.accounts.mint.to_account_info();
+    let data_len = 8 + IncoMint::LEN;
+    require!(mint.data_len() < data_len, CustomError::AlreadyInUse);
+
+    grow_account(
+        &mint,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        data_len,
+    )?;
+
+    emit!(ReallocateEvent {
+        owned: mint.key(),
+        extension_types: vec![],
+        data_len: data_len as u64,
+    });
+
+    Ok(())
+}
+
+pub fn set_max_supply<'info>(ctx: Context<'_, '_, '_, 'info, SetMaxSupply<'info>>, max_supply: AmountInput) -> Result<()> {
+    let mint = &mut ctx.accounts.mint;
+    require!(mint.is_initialized, CustomError::UninitializedState);
+    require!(mint.max_supply.is_none(), CustomError::MaxSupplyAlreadySet);
+
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    let multisig_signers = validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.mint_authority, ctx.remaining_accounts, multisig_signers);
+
+    let max_supply = amount_input_handle(&inco, &signer, max_supply)?;
+    mint.max_supply = COption::Some(max_supply);
+
+    // Grant allowance to the mint authority
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        max_supply,
+        mint_authority,
+        0,
+    )?;
+
+    allow_auditor(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint,
+        &[max_supply],
+    )?;
+
+    emit!(MaxSupplySetEvent {
+        mint: mint.key(),
+        max_supply,
+    });
+
+    Ok(())
+}
+
+/// Transfer ownership of a token account
+/// A new owner starts without the previous owner's delegate
+pub fn set_account_owner<'info>(ctx: Context<'_, '_, '_, 'info, SetAccountOwner<'info>>, new_owner: Pubkey) -> Result<()> {
     let account = &mut ctx.accounts.account;
     require!(account.state == AccountState::Initialized, CustomError::UninitializedState);
-    require!(account.owner == ctx.accounts.current_owner.key(), CustomError::OwnerMismatch);
+    require_transferable_account(&account.to_account_info())?;
+    let multisig_signers = validate_owner(&account.owner, &ctx.accounts.current_owner, ctx.remaining_accounts)?;
 
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.inco_lightning_program.to_account_info(),
+        Operation {
+            signer: operation_signer(&ctx.accounts.current_owner, ctx.remaining_accounts, multisig_signers),
+        }
+    );
     account.owner = new_owner;
+    account.delegate = COption::None;
+    account.delegated_amount = as_euint128(cpi_ctx, 0)?;
+    emit!(AuthorityChangedEvent {
+        owned: account.key(),
+        authority_type: AuthorityType::AccountOwner,
+        new_authority: Some(new_owner),
+    });
+
     Ok(())
 }
 
-pub fn set_close_authority(ctx: Context<SetCloseAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+pub fn set_close_authority<'info>(ctx: Context<'_, '_, '_, 'info, SetCloseAuthority<'info>>, new_authority: Option<Pubkey>) -> Result<()> {
     let account = &mut ctx.accounts.account;
     require!(account.state == AccountState::Initialized, CustomError::UninitializedState);
-    require!(account.owner == ctx.accounts.owner.key(), CustomError::OwnerMismatch);
+    validate_owner(&account.owner, &ctx.accounts.owner, ctx.remaining_accounts)?;
 
     account.close_authority = match new_authority {
         Some(authority) => COption::Some(authority),
         None => COption::None,
     };
 
+    emit!(AuthorityChangedEvent {
+        owned: account.key(),
+        authority_type: AuthorityType::CloseAccount,
+        new_authority,
+    });
+
+    Ok(())
+}
+
+/// Create the native (wrapped SOL) mint PDA
+/// It has no mint authority: supply only grows through sync_native
+pub fn initialize_native_mint(ctx: Context<InitializeNativeMint>) -> Result<()> {
+    let mint = &mut ctx.accounts.mint;
+
+    require!(!mint.is_initialized, CustomError::AlreadyInUse);
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.inco_lightning_program.to_account_info(),
+        Operation {
+            signer: ctx.accounts.payer.to_account_info(),
+        }
+    );
+    let zero_supply = as_euint128(cpi_ctx, 0)?;
+
+    mint.mint_authority = COption::None;
+    mint.supply = zero_supply;
+    mint.decimals = 9;
+    mint.is_initialized = true;
+    mint.freeze_authority = COption::None;
+    mint.auditor = COption::None;
+    mint.max_supply = COption::None;
+
+    emit!(InitializeMintEvent {
+        mint: mint.key(),
+        mint_authority: None,
+        freeze_authority: None,
+        decimals: mint.decimals,
+        supply: mint.supply,
+    });
+
+    Ok(())
+}
+
+/// Credit lamports deposited into a native account to its encrypted balance
+/// Lamports above the rent-exempt reserve are pooled on the native mint so that
+/// encrypted transfers never have to move lamports between accounts
+/// remaining_accounts:
+///   [0] allowance_account (mut)
+///   [1] owner_address (readonly)
+pub fn sync_native<'info>(ctx: Context<'_, '_, '_, 'info, SyncNative<'info>>) -> Result<()> {
+    let account = &mut ctx.accounts.account;
+    let mint = &mut ctx.accounts.mint;
+
+    let rent_reserve = match account.is_native {
+        COption::Some(reserve) => reserve,
+        COption::None => return Err(CustomError::NonNativeNotSupported.into()),
+    };
+
+    let account_info = account.to_account_info();
+    let account_lamports = account_info.lamports();
+    let deposit = account_lamports
+        .checked_sub(rent_reserve)
+        .ok_or(CustomError::NotRentExempt)?;
+    if deposit == 0 {
+        return Ok(());
+    }
+
+    let mint_info = mint.to_account_info();
+    let mint_lamports = mint_info.lamports();
+    **mint_info.lamports.borrow_mut() = mint_lamports
+        .checked_add(deposit)
+        .ok_or(CustomError::Overflow)?;
+    **account_info.lamports.borrow_mut() = rent_reserve;
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.payer.to_account_info();
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let amount = as_euint128(cpi_ctx, deposit as u128)?;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_supply = e_add(cpi_ctx2, mint.supply, amount, 0u8)?;
+    mint.supply = new_supply;
+
+    let cpi_ctx3 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_balance = e_add(cpi_ctx3, account.amount, amount, 0u8)?;
+    account.amount = new_balance;
+
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        new_balance,
+        account.owner,
+        0,
+    )?;
+
+    emit!(SyncNativeEvent {
+        account: account.key(),
+        lamports: deposit,
+        new_balance,
+        new_supply,
+    });
+
+    Ok(())
+}
+
+/// Unwrap the whole balance of a native account back to lamports
+/// `balance_plaintext` is the attested plaintext of `account.amount`
+/// remaining_accounts (after any multisig signers):
+///   [0] allowance_account (mut)
+///   [1] owner_address (readonly)
+pub fn unwrap_native<'info>(
+    ctx: Context<'_, '_, '_, 'info, UnwrapNative<'info>>,
+    balance_plaintext: Vec<u8>
+) -> Result<()> {
+    let account = &mut ctx.accounts.account;
+    let mint = &mut ctx.accounts.mint;
+
+    require!(account.is_native.is_some(), CustomError::NonNativeNotSupported);
+    let multisig_signers = validate_owner(&account.owner, &ctx.accounts.owner, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.owner, ctx.remaining_accounts, multisig_signers);
+
+    let balance = verify_attested_plaintext(
+        &inco,
+        &ctx.accounts.instructions,
+        &signer,
+        account.amount,
+        balance_plaintext,
+    )?;
+    let lamports = u64::try_from(balance).map_err(|_| CustomError::Overflow)?;
+
+    // The pooled lamports live on the native mint, above its own rent reserve
+    let mint_info = mint.to_account_info();
+    let mint_lamports = mint_info
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(CustomError::InsufficientFunds)?;
+    require!(
+        mint_lamports >= Rent::get()?.minimum_balance(mint_info.data_len()),
+        CustomError::InsufficientFunds
+    );
+    let dest_starting_lamports = ctx.accounts.destination.lamports();
+    **ctx.accounts.destination.lamports.borrow_mut() = dest_starting_lamports
+        .checked_add(lamports)
+        .ok_or(CustomError::Overflow)?;
+    **mint_info.lamports.borrow_mut() = mint_lamports;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let amount = as_euint128(cpi_ctx, balance)?;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_supply = e_sub(cpi_ctx2, mint.supply, amount, 0u8)?;
+    mint.supply = new_supply;
+
+    let cpi_ctx3 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_balance = as_euint128(cpi_ctx3, 0)?;
+    account.amount = new_balance;
+
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_balance,
+        account.owner,
+        0,
+    )?;
+
+    emit!(UnwrapNativeEvent {
+        account: account.key(),
+        destination: ctx.accounts.destination.key(),
+        lamports,
+        new_balance,
+        new_supply,
+    });
+
+    Ok(())
+}
+
+/// Set a mint or account authority (SPL Token compatible)
+/// MintTokens and FreezeAccount expect `owned` to be an IncoMint,
+/// AccountOwner and CloseAccount expect an IncoAccount
+pub fn set_authority<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetAuthority<'info>>,
+    authority_type: AuthorityType,
+    new_authority: Option<Pubkey>
+) -> Result<()> {
+    let owned = &ctx.accounts.owned;
+    require!(owned.owner == &crate::ID, CustomError::AuthorityTypeNotSupported);
+
+    let new_authority = match new_authority {
+        Some(authority) => COption::Some(authority),
+        None => COption::None,
+    };
+
+    match authority_type {
+        AuthorityType::MintTokens | AuthorityType::FreezeAccount => {
+            let mut mint = IncoMint::try_deserialize(&mut &owned.try_borrow_data()?[..])
+                .map_err(|_| CustomError::AuthorityTypeNotSupported)?;
+            require!(mint.is_initialized, CustomError::UninitializedState);
+
+            let current_authority = match authority_type {
+                AuthorityType::MintTokens => &mut mint.mint_authority,
+                _ => &mut mint.freeze_authority,
+            };
+            let current = match current_authority {
+                COption::Some(authority) => *authority,
+                COption::None if authority_type == AuthorityType::MintTokens => {
+                    return Err(CustomError::FixedSupply.into())
+                }
+                COption::None => return Err(CustomError::MintCannotFreeze.into()),
+            };
+            validate_owner(&current, &ctx.accounts.current_authority, ctx.remaining_accounts)?;
+
+            *current_authority = new_authority.clone();
+            mint.try_serialize(&mut &mut owned.try_borrow_mut_data()?[..])?;
+        }
+        AuthorityType::AccountOwner | AuthorityType::CloseAccount => {
+            let mut account = IncoAccount::try_deserialize(&mut &owned.try_borrow_data()?[..])
+                .map_err(|_| CustomError::AuthorityTypeNotSupported)?;
+            require!(account.state == AccountState::Initialized, CustomError::UninitializedState);
+
+            let current = match (authority_type, &account.close_authority) {
+                (AuthorityType::CloseAccount, COption::Some(close_authority)) => *close_authority,
+                _ => account.owner,
+            };
+            let multisig_signers = validate_owner(&current, &ctx.accounts.current_authority, ctx.remaining_accounts)?;
+
+            if authority_type == AuthorityType::AccountOwner {
+                require_transferable_account(owned)?;
+                account.owner = match new_authority {
+                    COption::Some(owner) => owner,
+                    COption::None => return Err(CustomError::InvalidInstruction.into()),
+                };
+                // A new owner starts without the previous owner's delegate
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.inco_lightning_program.to_account_info(),
+                    Operation {
+                        signer: operation_signer(&ctx.accounts.current_authority, ctx.remaining_accounts, multisig_signers),
+                    }
+                );
+                account.delegate = COption::None;
+                account.delegated_amount = as_euint128(cpi_ctx, 0)?;
+            } else {
+                account.close_authority = new_authority.clone();
+            }
+            account.try_serialize(&mut &mut owned.try_borrow_mut_data()?[..])?;
+        }
+    }
+
+    emit!(AuthorityChangedEvent {
+        owned: owned.key(),
+        authority_type,
+        new_authority: new_authority.to_option(),
+    });
+
     Ok(())
 }
 
@@ -501,6 +1461,27 @@ pub struct InitializeMint<'info> {
     pub inco_lightning_program: AccountInfo<'info>,
 }
 
+#[derive(Accounts)]
+pub struct InitializeMultisig<'info> {
+    #[account(init, payer = payer, space = 8 + IncoMultisig::LEN)]
+    pub multisig: Account<'info, IncoMultisig>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeNativeMint<'info> {
+    #[account(init, payer = payer, space = 8 + IncoMint::LEN, seeds = [NATIVE_MINT_SEED], bump)]
+    pub mint: Account<'info, IncoMint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
 #[derive(Accounts)]
 pub struct InitializeAccount<'info> {
     #[account(init, payer = payer, space = 8 + IncoAccount::LEN)]
@@ -527,8 +1508,9 @@ pub struct IncoMintTo<'info> {
         constraint = account.mint == mint.key() @ CustomError::MintMismatch,
     )]
     pub account: Account<'info, IncoAccount>,
+    /// CHECK: Mint authority, validated against the mint (single key or IncoMultisig)
     #[account(mut)]
-    pub mint_authority: Signer<'info>,
+    pub mint_authority: UncheckedAccount<'info>,
     /// CHECK: Inco Lightning program
     #[account(address = INCO_LIGHTNING_ID)]
     pub inco_lightning_program: AccountInfo<'info>,
@@ -550,8 +1532,30 @@ pub struct IncoTransfer<'info> {
         constraint = destination.mint == source.mint @ CustomError::MintMismatch,
     )]
     pub destination: Account<'info, IncoAccount>,
+    #[account(constraint = mint.key() == source.mint @ CustomError::MintMismatch)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Owner or delegate, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct IncoTransferBatch<'info> {
+    #[account(
+        mut,
+        constraint = source.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+        constraint = source.state != AccountState::Frozen @ CustomError::AccountFrozen,
+    )]
+    pub source: Account<'info, IncoAccount>,
+    #[account(constraint = mint.key() == source.mint @ CustomError::MintMismatch)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Owner or delegate, validated in the instruction (single key or IncoMultisig)
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub authority: UncheckedAccount<'info>,
     /// CHECK: Inco Lightning program
     #[account(address = INCO_LIGHTNING_ID)]
     pub inco_lightning_program: AccountInfo<'info>,
@@ -567,10 +1571,13 @@ pub struct IncoApprove<'info> {
         constraint = source.owner == owner.key() @ CustomError::OwnerMismatch,
     )]
     pub source: Account<'info, IncoAccount>,
+    #[account(constraint = mint.key() == source.mint @ CustomError::MintMismatch)]
+    pub mint: Account<'info, IncoMint>,
     /// CHECK: Delegate address
     pub delegate: UncheckedAccount<'info>,
+    /// CHECK: Account owner, validated in the instruction (single key or IncoMultisig)
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub owner: UncheckedAccount<'info>,
     /// CHECK: Inco Lightning program
     #[account(address = INCO_LIGHTNING_ID)]
     pub inco_lightning_program: AccountInfo<'info>,
@@ -585,8 +1592,9 @@ pub struct IncoRevoke<'info> {
         constraint = source.owner == owner.key() @ CustomError::OwnerMismatch,
     )]
     pub source: Account<'info, IncoAccount>,
+    /// CHECK: Account owner, validated in the instruction (single key or IncoMultisig)
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub owner: UncheckedAccount<'info>,
     /// CHECK: Inco Lightning program
     #[account(address = INCO_LIGHTNING_ID)]
     pub inco_lightning_program: AccountInfo<'info>,
@@ -603,8 +1611,9 @@ pub struct IncoBurn<'info> {
     pub account: Account<'info, IncoAccount>,
     #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
     pub mint: Account<'info, IncoMint>,
+    /// CHECK: Owner or delegate, validated in the instruction (single key or IncoMultisig)
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub authority: UncheckedAccount<'info>,
     /// CHECK: Inco Lightning program
     #[account(address = INCO_LIGHTNING_ID)]
     pub inco_lightning_program: AccountInfo<'info>,
@@ -621,8 +1630,9 @@ pub struct FreezeAccount<'info> {
     pub account: Account<'info, IncoAccount>,
     #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
     pub mint: Account<'info, IncoMint>,
+    /// CHECK: Freeze authority, validated against the mint (single key or IncoMultisig)
     #[account(mut)]
-    pub freeze_authority: Signer<'info>,
+    pub freeze_authority: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
@@ -635,43 +1645,92 @@ pub struct ThawAccount<'info> {
     pub account: Account<'info, IncoAccount>,
     #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
     pub mint: Account<'info, IncoMint>,
+    /// CHECK: Freeze authority, validated against the mint (single key or IncoMultisig)
     #[account(mut)]
-    pub freeze_authority: Signer<'info>,
+    pub freeze_authority: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
 pub struct CloseAccount<'info> {
-    #[account(mut, constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState)]
+    #[account(
+        mut,
+        close = destination,
+        constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState,
+    )]
     pub account: Account<'info, IncoAccount>,
     /// CHECK: Destination for lamports
     #[account(mut)]
     pub destination: AccountInfo<'info>,
+    /// CHECK: Owner or close authority, validated in the instruction (single key or IncoMultisig)
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar holding the Ed25519 decryption attestation
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
 pub struct SetMintAuthority<'info> {
     #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
     pub mint: Account<'info, IncoMint>,
+    /// CHECK: Current authority, validated against the mint (single key or IncoMultisig)
     #[account(mut)]
-    pub current_authority: Signer<'info>,
+    pub current_authority: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
 pub struct SetFreezeAuthority<'info> {
     #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
     pub mint: Account<'info, IncoMint>,
+    /// CHECK: Current authority, validated against the mint (single key or IncoMultisig)
+    #[account(mut)]
+    pub current_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuditor<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated against the mint (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMint<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMaxSupply<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated against the mint (single key or IncoMultisig)
     #[account(mut)]
-    pub current_authority: Signer<'info>,
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct SetAccountOwner<'info> {
     #[account(mut, constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState)]
     pub account: Account<'info, IncoAccount>,
+    /// CHECK: Current owner, validated against the account (single key or IncoMultisig)
     #[account(mut)]
-    pub current_owner: Signer<'info>,
+    pub current_owner: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
@@ -682,6 +1741,63 @@ pub struct SetCloseAuthority<'info> {
         constraint = account.owner == owner.key() @ CustomError::OwnerMismatch,
     )]
     pub account: Account<'info, IncoAccount>,
+    /// CHECK: Account owner, validated against the account (single key or IncoMultisig)
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuthority<'info> {
+    /// CHECK: IncoMint or IncoAccount, deserialized according to the authority type
+    #[account(mut)]
+    pub owned: UncheckedAccount<'info>,
+    /// CHECK: Current authority, validated in the instruction (single key or IncoMultisig)
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub current_authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SyncNative<'info> {
+    #[account(
+        mut,
+        constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState,
+        constraint = account.mint == mint.key() @ CustomError::MintMismatch,
+    )]
+    pub account: Account<'info, IncoAccount>,
+    #[account(mut, seeds = [NATIVE_MINT_SEED], bump)]
+    pub mint: Account<'info, IncoMint>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnwrapNative<'info> {
+    #[account(
+        mut,
+        constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState,
+        constraint = account.mint == mint.key() @ CustomError::MintMismatch,
+    )]
+    pub account: Account<'info, IncoAccount>,
+    #[account(mut, seeds = [NATIVE_MINT_SEED], bump)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Destination for lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+    /// CHECK: Account owner, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar holding the Ed25519 decryption attestation
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
 }
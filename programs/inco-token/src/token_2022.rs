@@ -1,54 +1,471 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
 use anchor_lang::solana_program::pubkey::Pubkey;
-use inco_lightning::cpi::accounts::{Operation, Allow};
-use inco_lightning::cpi::{e_add, e_ge, e_select, e_sub, new_euint128, as_euint128, allow};
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::{e_add, e_ge, e_mul, e_select, e_shr, e_sub, new_euint128, as_euint128};
 use inco_lightning::types::Euint128;
 use inco_lightning::ID as INCO_LIGHTNING_ID;
-pub use crate::{AccountState, COption, CustomError, IncoMint, IncoAccount};
+pub use crate::{AccountState, AmountInput, COption, CustomError, IncoMint, IncoAccount};
+use crate::events::*;
+use crate::allowance::{allow_auditor, call_allow_from_remaining};
+use crate::token::{
+    amount_input_handle, capped_mint_amount, native_reserve, operation_signer, validate_owner, verify_attested_plaintext,
+    verify_zero_handles,
+};
+use crate::extension::{
+    get_extension, get_extension_types, init_extension, reallocate_extensions, set_extension, DefaultAccountState,
+    ExtensionType, InterestBearingConfig, MemoTransfer, MetadataPointer, MintCloseAuthority, NonTransferable,
+    NonTransferableAccount,
+    PermanentDelegate, TransferFee, TransferFeeAmount, TransferFeeConfig, TransferHook, ACCOUNT_EXTENSIONS_OFFSET,
+    MINT_EXTENSIONS_OFFSET,
+};
 
 pub const TOKEN_2022_ID: Pubkey = anchor_lang::solana_program::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
 
-// ========== HELPER FUNCTION ==========
+/// Transfer fees are expressed in basis points of the transferred amount
+pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+/// Fractional bits of the fee rate; Inco Lightning has no encrypted division
+pub const FEE_RATE_BITS: u32 = 64;
+/// Epochs until a fee set with set_transfer_fee is charged, so that at least one
+/// full epoch passes under the announced fee before it applies
+pub const TRANSFER_FEE_EPOCH_DELAY: u64 = 2;
+
+// ========== MINT INITIALIZATION HELPERS ==========
+
+/// Reject a mint extension initialized after token accounts of the mint may exist.
+/// The current instruction must be a top-level instruction of this program in the
+/// transaction that initializes the mint, with only reallocate_mint and mint
+/// extension initializers between initialize_mint and it.
+pub fn require_mint_initialization(mint: &Pubkey, instructions: &AccountInfo) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let current = load_instruction_at_checked(current_index, instructions)?;
+    require!(current.program_id == crate::ID, CustomError::MintAlreadyInUse);
+
+    for index in (0..current_index).rev() {
+        let previous = load_instruction_at_checked(index, instructions)?;
+        require!(previous.program_id == crate::ID, CustomError::MintAlreadyInUse);
+        let initializes_mint = previous.data.starts_with(crate::instruction::InitializeMint::DISCRIMINATOR);
+        if initializes_mint && previous.accounts.first().map(|meta| meta.pubkey) == Some(*mint) {
+            return Ok(());
+        }
+        let initializes_extension = [
+            crate::instruction::ReallocateMint::DISCRIMINATOR,
+            crate::instruction::InitializeMetadataPointer::DISCRIMINATOR,
+            crate::instruction::InitializeTransferHook::DISCRIMINATOR,
+            crate::instruction::InitializeDefaultAccountState::DISCRIMINATOR,
+            crate::instruction::InitializeNonTransferableMint::DISCRIMINATOR,
+            crate::instruction::InitializePermanentDelegate::DISCRIMINATOR,
+            crate::instruction::InitializeInterestBearingMint::DISCRIMINATOR,
+            crate::instruction::InitializeMintCloseAuthority::DISCRIMINATOR,
+            crate::instruction::InitializeTransferFeeConfig::DISCRIMINATOR,
+        ]
+        .iter()
+        .any(|discriminator| previous.data.starts_with(discriminator));
+        require!(initializes_extension, CustomError::MintAlreadyInUse);
+    }
+    Err(CustomError::MintAlreadyInUse.into())
+}
+
+// ========== TRANSFER FEE HELPERS ==========
 
-/// Helper to call allow with accounts from remaining_accounts
-fn call_allow_from_remaining<'info>(
+/// Fee rate of `basis_points` with FEE_RATE_BITS fractional bits, rounded down
+pub fn fee_rate(basis_points: u16) -> u128 {
+    ((basis_points as u128) << FEE_RATE_BITS) / MAX_FEE_BASIS_POINTS as u128
+}
+
+/// Fee on `amount`: ceil(amount * basis_points / 10000), capped at the maximum fee
+/// The rate is applied as a fixed-point multiplication and a rounding-up shift,
+/// which is exact for amounts below 2^64 / 10000
+pub fn calculate_transfer_fee<'info>(
     inco_program: &AccountInfo<'info>,
     signer: &AccountInfo<'info>,
+    transfer_fee: &TransferFee,
+    amount: Euint128,
+) -> Result<Euint128> {
+    let cpi_ctx = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let rate = as_euint128(cpi_ctx, fee_rate(transfer_fee.transfer_fee_basis_points))?;
+
+    let cpi_ctx2 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let scaled = e_mul(cpi_ctx2, amount, rate, 0u8)?;
+
+    let cpi_ctx3 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let rounding = as_euint128(cpi_ctx3, (1u128 << FEE_RATE_BITS) - 1)?;
+
+    let cpi_ctx4 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let rounded = e_add(cpi_ctx4, scaled, rounding, 0u8)?;
+
+    let cpi_ctx5 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let rate_bits = as_euint128(cpi_ctx5, FEE_RATE_BITS as u128)?;
+
+    let cpi_ctx6 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let raw_fee = e_shr(cpi_ctx6, rounded, rate_bits, 0u8)?;
+
+    let cpi_ctx7 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    let within_maximum = e_ge(cpi_ctx7, transfer_fee.maximum_fee, raw_fee, 0u8)?;
+
+    let cpi_ctx8 = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+    e_select(cpi_ctx8, within_maximum, raw_fee, transfer_fee.maximum_fee, 0u8)
+}
+
+/// Give a new token account the account extensions its mint's extensions call for:
+/// a TransferFeeAmount withholding `zero_value` for fee mints, so every destination
+/// of transfer_checked has one, and a NonTransferableAccount for non-transferable mints
+pub fn init_account_extensions<'info>(
+    account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
     system_program: &AccountInfo<'info>,
-    remaining_accounts: &[AccountInfo<'info>],
-    handle: Euint128,
-    allowed_pubkey: Pubkey,
-    account_offset: usize,
+    zero_value: Euint128,
 ) -> Result<()> {
-    if remaining_accounts.len() < account_offset + 2 {
-        return Err(CustomError::InvalidInstruction.into());
+    let mint_extension_types = get_extension_types(&mint.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    let has_transfer_fee = mint_extension_types.contains(&ExtensionType::TransferFeeConfig);
+    let is_non_transferable = mint_extension_types.contains(&ExtensionType::NonTransferable);
+
+    let mut extension_types = Vec::new();
+    if has_transfer_fee {
+        extension_types.push(ExtensionType::TransferFeeAmount);
     }
-    
-    let allowance_account = &remaining_accounts[account_offset];
-    let allowed_address = &remaining_accounts[account_offset + 1];
-    
-    let cpi_ctx = CpiContext::new(
-        inco_program.clone(),
-        Allow {
-            allowance_account: allowance_account.clone(),
-            signer: signer.clone(),
-            allowed_address: allowed_address.clone(),
-            system_program: system_program.clone(),
+    if is_non_transferable {
+        extension_types.push(ExtensionType::NonTransferableAccount);
+    }
+    if extension_types.is_empty() {
+        return Ok(());
+    }
+    reallocate_extensions(account, payer, system_program, ACCOUNT_EXTENSIONS_OFFSET, &extension_types)?;
+
+    let mut data = account.try_borrow_mut_data()?;
+    if has_transfer_fee {
+        init_extension(&mut data, ACCOUNT_EXTENSIONS_OFFSET, &TransferFeeAmount { withheld_amount: zero_value })?;
+    }
+    if is_non_transferable {
+        init_extension(&mut data, ACCOUNT_EXTENSIONS_OFFSET, &NonTransferableAccount {})?;
+    }
+    Ok(())
+}
+
+/// Fees withheld on a token account, if it holds a TransferFeeAmount
+pub fn withheld_amount(account: &AccountInfo) -> Result<Option<Euint128>> {
+    let fee_amount = get_extension::<TransferFeeAmount>(&account.try_borrow_data()?, ACCOUNT_EXTENSIONS_OFFSET)?;
+    Ok(fee_amount.map(|fee_amount| fee_amount.withheld_amount))
+}
+
+/// Reject mints with a transfer fee in instructions that cannot charge it
+pub fn require_no_transfer_fee(mint: &AccountInfo) -> Result<()> {
+    let extension_types = get_extension_types(&mint.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    require!(
+        !extension_types.contains(&ExtensionType::TransferFeeConfig),
+        CustomError::MintHasTransferFee
+    );
+    Ok(())
+}
+
+// ========== TRANSFER HOOK HELPERS ==========
+
+/// Seed of the PDA, under the hook program, describing the extra accounts it needs
+pub const EXTRA_ACCOUNT_METAS_SEED: &[u8] = b"extra-account-metas";
+/// First 8 bytes of sha256("inco-transfer-hook-interface:execute")
+pub const TRANSFER_HOOK_EXECUTE_DISCRIMINATOR: [u8; 8] = [80, 224, 187, 159, 205, 172, 116, 125];
+
+/// ExtraAccountMeta whose address_config is the account's address
+pub const EXTRA_ACCOUNT_FIXED: u8 = 0;
+/// ExtraAccountMeta whose address_config packs the seeds of a PDA of the hook program
+pub const EXTRA_ACCOUNT_HOOK_PDA: u8 = 1;
+/// Packed seed [SEED_LITERAL, len, bytes...]
+pub const SEED_LITERAL: u8 = 1;
+/// Packed seed [SEED_ACCOUNT_KEY, index]: the key of an earlier execute account
+pub const SEED_ACCOUNT_KEY: u8 = 3;
+
+/// Account a transfer hook needs beyond the execute accounts, as listed in its
+/// extra-account-metas PDA. The PDA data is TRANSFER_HOOK_EXECUTE_DISCRIMINATOR,
+/// a u32 byte length and a u32 count (LE), then `count` entries of LEN bytes.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ExtraAccountMeta {
+    /// EXTRA_ACCOUNT_FIXED or EXTRA_ACCOUNT_HOOK_PDA
+    pub discriminator: u8,
+    /// Address, or zero-terminated packed seeds
+    pub address_config: [u8; 32],
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl ExtraAccountMeta {
+    pub const LEN: usize = 1 + 32 + 1 + 1;
+}
+
+/// Extra-account-metas PDA of `program_id` for `mint`
+pub fn extra_account_metas_address(mint: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[EXTRA_ACCOUNT_METAS_SEED, mint.as_ref()], program_id).0
+}
+
+/// Entries of an extra-account-metas PDA; a PDA that was never created lists none
+fn read_extra_account_metas(metas_account: &AccountInfo, program_id: &Pubkey) -> Result<Vec<ExtraAccountMeta>> {
+    if metas_account.data_is_empty() {
+        return Ok(Vec::new());
+    }
+    require!(metas_account.owner == program_id, CustomError::InvalidTransferHookAccounts);
+
+    let data = metas_account.try_borrow_data()?;
+    require!(
+        data.len() >= 16 && data[..8] == TRANSFER_HOOK_EXECUTE_DISCRIMINATOR,
+        CustomError::InvalidTransferHookAccounts
+    );
+    let length = u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    let count = u32::from_le_bytes([data[12], data[13], data[14], data[15]]) as usize;
+    require!(
+        length == 4 + count * ExtraAccountMeta::LEN && data.len() >= 12 + length,
+        CustomError::InvalidTransferHookAccounts
+    );
+
+    data[16..12 + length]
+        .chunks_exact(ExtraAccountMeta::LEN)
+        .map(|entry| {
+            ExtraAccountMeta::try_from_slice(entry).map_err(|_| CustomError::InvalidTransferHookAccounts.into())
+        })
+        .collect()
+}
+
+/// Address an extra account must have. `accounts` are the execute accounts
+/// resolved so far, which account key seeds index into.
+fn resolve_extra_account(meta: &ExtraAccountMeta, program_id: &Pubkey, accounts: &[AccountInfo]) -> Result<Pubkey> {
+    match meta.discriminator {
+        EXTRA_ACCOUNT_FIXED => Ok(Pubkey::new_from_array(meta.address_config)),
+        EXTRA_ACCOUNT_HOOK_PDA => {
+            let config = &meta.address_config;
+            let mut seeds: Vec<&[u8]> = Vec::new();
+            let mut cursor = 0;
+            while cursor < config.len() && config[cursor] != 0 {
+                let argument = *config.get(cursor + 1).ok_or(CustomError::InvalidTransferHookAccounts)? as usize;
+                match config[cursor] {
+                    SEED_LITERAL => {
+                        let seed = config
+                            .get(cursor + 2..cursor + 2 + argument)
+                            .ok_or(CustomError::InvalidTransferHookAccounts)?;
+                        seeds.push(seed);
+                        cursor += 2 + argument;
+                    }
+                    SEED_ACCOUNT_KEY => {
+                        let account = accounts.get(argument).ok_or(CustomError::InvalidTransferHookAccounts)?;
+                        seeds.push(account.key.as_ref());
+                        cursor += 2;
+                    }
+                    _ => return Err(CustomError::InvalidTransferHookAccounts.into()),
+                }
+            }
+            Ok(Pubkey::find_program_address(&seeds, program_id).0)
         }
+        _ => Err(CustomError::InvalidTransferHookAccounts.into()),
+    }
+}
+
+/// Reject mints with a transfer hook in instructions that do not invoke it
+pub fn require_no_transfer_hook(mint: &AccountInfo) -> Result<()> {
+    let extension_types = get_extension_types(&mint.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    require!(
+        !extension_types.contains(&ExtensionType::TransferHook),
+        CustomError::MintHasTransferHook
+    );
+    Ok(())
+}
+
+/// Split remaining_accounts into the allowance accounts and the trailing accounts
+/// of the transfer extensions, which start at the mint's hook program or at the
+/// instructions sysvar, whichever comes first:
+///   [allowance accounts..., instructions_sysvar, hook_program, extra_account_metas, ...]
+/// The sysvar comes before the hook accounts, which run to the end, so a hook may
+/// list the sysvar among its own extra accounts.
+/// The allowance accounts keep their positions whether or not either is present.
+pub fn split_extension_accounts<'a, 'info>(
+    mint: &AccountInfo<'info>,
+    remaining_accounts: &'a [AccountInfo<'info>],
+) -> Result<(&'a [AccountInfo<'info>], &'a [AccountInfo<'info>])> {
+    let hook = get_extension::<TransferHook>(&mint.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    let hook_program = match hook.map(|hook| hook.program_id) {
+        Some(COption::Some(program_id)) => Some(program_id),
+        _ => None,
+    };
+    let position = remaining_accounts.iter().position(|account| {
+        Some(account.key()) == hook_program || account.key() == anchor_lang::solana_program::sysvar::instructions::ID
+    });
+    Ok(remaining_accounts.split_at(position.unwrap_or(remaining_accounts.len())))
+}
+
+/// Invoke the mint's transfer hook, if one is set, with the transferred amount handle.
+/// The hook is found in remaining_accounts by its address and must be followed by
+/// its extra-account-metas PDA and then exactly the extra accounts the PDA lists,
+/// in order and with at least the listed privileges, as the last remaining accounts:
+///   [hook_program, extra_account_metas, extra_account_1, ...]
+/// Execute data: TRANSFER_HOOK_EXECUTE_DISCRIMINATOR followed by the u128 handle (LE).
+/// Execute accounts: [source, mint, destination, authority, extra_account_metas, extra_account_1, ...]
+/// The authority's signature is not forwarded to the hook.
+/// A failing hook fails the transfer.
+pub fn invoke_transfer_hook<'info>(
+    source: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: Euint128,
+) -> Result<()> {
+    let hook = get_extension::<TransferHook>(&mint.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    let program_id = match hook.map(|hook| hook.program_id) {
+        Some(COption::Some(program_id)) => program_id,
+        _ => return Ok(()),
+    };
+
+    let position = remaining_accounts
+        .iter()
+        .position(|account| account.key() == program_id)
+        .ok_or(CustomError::MissingTransferHookAccounts)?;
+    let hook_program = &remaining_accounts[position];
+    let extra_accounts = &remaining_accounts[position + 1..];
+    let metas_account = extra_accounts.first().ok_or(CustomError::MissingTransferHookAccounts)?;
+    require!(
+        metas_account.key() == extra_account_metas_address(&mint.key(), &program_id),
+        CustomError::MissingTransferHookAccounts
+    );
+    let extra_account_metas = read_extra_account_metas(metas_account, &program_id)?;
+    require!(
+        extra_accounts.len() == 1 + extra_account_metas.len(),
+        CustomError::InvalidTransferHookAccounts
+    );
+
+    let mut metas = vec![
+        AccountMeta::new_readonly(source.key(), false),
+        AccountMeta::new_readonly(mint.key(), false),
+        AccountMeta::new_readonly(destination.key(), false),
+        AccountMeta::new_readonly(authority.key(), false),
+        AccountMeta::new_readonly(metas_account.key(), false),
+    ];
+    let mut account_infos = vec![
+        source.clone(),
+        mint.clone(),
+        destination.clone(),
+        authority.clone(),
+        metas_account.clone(),
+    ];
+    for (extra_account_meta, account) in extra_account_metas.iter().zip(&extra_accounts[1..]) {
+        let address = resolve_extra_account(extra_account_meta, &program_id, &account_infos)?;
+        require!(
+            account.key() == address
+                && (account.is_signer || !extra_account_meta.is_signer)
+                && (account.is_writable || !extra_account_meta.is_writable),
+            CustomError::InvalidTransferHookAccounts
+        );
+        metas.push(AccountMeta {
+            pubkey: address,
+            is_signer: extra_account_meta.is_signer,
+            is_writable: extra_account_meta.is_writable,
+        });
+        account_infos.push(account.clone());
+    }
+    account_infos.push(hook_program.clone());
+
+    let mut data = TRANSFER_HOOK_EXECUTE_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount.0.to_le_bytes());
+
+    invoke(&Instruction { program_id, accounts: metas, data }, &account_infos)?;
+    Ok(())
+}
+
+// ========== MEMO TRANSFER HELPERS ==========
+
+/// Reject a transfer into an account that requires memos unless the instruction
+/// right before it in the transaction is this program's build_memo,
+/// build_memo_with_recipients or build_long_memo. The instructions sysvar is looked up in `extension_accounts`
+/// by its address.
+pub fn require_transfer_memo(destination: &AccountInfo, extension_accounts: &[AccountInfo]) -> Result<()> {
+    let memo_transfer = get_extension::<MemoTransfer>(&destination.try_borrow_data()?, ACCOUNT_EXTENSIONS_OFFSET)?;
+    if !memo_transfer.is_some_and(|memo_transfer| memo_transfer.require_incoming_transfer_memos) {
+        return Ok(());
+    }
+
+    let instructions = extension_accounts
+        .iter()
+        .find(|account| account.key() == anchor_lang::solana_program::sysvar::instructions::ID)
+        .ok_or(CustomError::NoMemo)?;
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, CustomError::NoMemo);
+    let previous = load_instruction_at_checked(current_index as usize - 1, instructions)?;
+    let is_memo = previous.data.starts_with(crate::instruction::BuildMemo::DISCRIMINATOR)
+        || previous.data.starts_with(crate::instruction::BuildMemoWithRecipients::DISCRIMINATOR)
+        || previous.data.starts_with(crate::instruction::BuildLongMemo::DISCRIMINATOR);
+    require!(previous.program_id == crate::ID && is_memo, CustomError::NoMemo);
+    Ok(())
+}
+
+// ========== NON-TRANSFERABLE HELPERS ==========
+
+/// Reject transfers and delegations of non-transferable mints
+pub fn require_transferable(mint: &AccountInfo) -> Result<()> {
+    let extension_types = get_extension_types(&mint.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    require!(
+        !extension_types.contains(&ExtensionType::NonTransferable),
+        CustomError::NonTransferable
+    );
+    Ok(())
+}
+
+/// Reject owner changes of accounts of non-transferable mints, which would
+/// otherwise hand their tokens to someone else
+pub fn require_transferable_account(account: &AccountInfo) -> Result<()> {
+    let extension_types = get_extension_types(&account.try_borrow_data()?, ACCOUNT_EXTENSIONS_OFFSET)?;
+    require!(
+        !extension_types.contains(&ExtensionType::NonTransferableAccount),
+        CustomError::NonTransferable
     );
-    
-    allow(cpi_ctx, handle.0, true, allowed_pubkey)?;
     Ok(())
 }
 
+/// Reject mints whose transfers need more than moving the amount: used by
+/// instructions that move tokens outside transfer and transfer_checked
+pub fn require_plain_transfers(mint: &AccountInfo) -> Result<()> {
+    require_no_transfer_fee(mint)?;
+    require_no_transfer_hook(mint)?;
+    require_transferable(mint)
+}
+
+// ========== INTEREST BEARING HELPERS ==========
+
+/// Fractional bits of the accrued interest factor applied to encrypted balances.
+/// accrued_factor keeps the factor below 2^64, leaving 16 integer bits, so a u64
+/// balance times the factor fits in 128 bits.
+pub const INTEREST_FACTOR_BITS: u32 = 48;
+
+// ========== PERMANENT DELEGATE HELPERS ==========
+
+/// Permanent delegate of `mint`, if it has one
+pub fn permanent_delegate(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    let extension = get_extension::<PermanentDelegate>(&mint.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    Ok(extension.and_then(|extension| extension.delegate.to_option()))
+}
+
+// ========== DEFAULT ACCOUNT STATE HELPERS ==========
+
+/// State a new token account of `mint` starts in
+pub fn default_account_state(mint: &AccountInfo) -> Result<AccountState> {
+    let default_state = get_extension::<DefaultAccountState>(&mint.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    Ok(default_state.map_or(AccountState::Initialized, |default_state| default_state.state))
+}
+
 // ========== TOKEN 2022 CHECKED FUNCTIONS ==========
 
 /// Transfer checked - validates decimals match mint
-/// remaining_accounts:
+/// When the mint has a transfer fee, the destination is credited the amount net
+/// of the fee and the fee is withheld on the destination, in the TransferFeeAmount
+/// it was given at initialization.
+/// remaining_accounts (after any multisig signers):
 ///   [0] source_allowance_account (mut)
 ///   [1] source_owner_address (readonly)
 ///   [2] dest_allowance_account (mut)
 ///   [3] dest_owner_address (readonly)
+///   [4] delegated_allowance_account (mut) - only when a delegate signs
+///   [5] source_owner_address (readonly)
+///   [6] delegated_allowance_account (mut) - only when a delegate signs
+///   [7] delegate_address (readonly)
+///   then the instructions sysvar when the destination requires memos
+///   then the transfer hook accounts when the mint has a hook (see invoke_transfer_hook)
 pub fn transfer_checked<'info>(
     ctx: Context<'_, '_, '_, 'info, TransferChecked<'info>>,
     ciphertext: Vec<u8>,
@@ -66,21 +483,29 @@ pub fn transfer_checked<'info>(
     require!(source.mint == mint.key(), CustomError::MintMismatch);
     require!(destination.mint == mint.key(), CustomError::MintMismatch);
     require!(mint.decimals == decimals, CustomError::MintDecimalsMismatch);
+    require_transferable(&mint.to_account_info())?;
 
     if source.key() == destination.key() {
         return Ok(());
     }
 
     let authority_key = ctx.accounts.authority.key();
-    if source.owner != authority_key {
+    // The mint's permanent delegate acts with the owner's rights
+    let is_permanent_delegate = permanent_delegate(&mint.to_account_info())? == Some(authority_key);
+    let is_delegate = source.owner != authority_key && !is_permanent_delegate;
+    if is_delegate {
         match source.delegate {
             COption::Some(delegate) if delegate == authority_key => {}
             _ => return Err(CustomError::OwnerMismatch.into()),
         }
     }
+    let multisig_signers = validate_owner(&authority_key, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    let (remaining_accounts, extension_accounts) =
+        split_extension_accounts(&mint.to_account_info(), &ctx.remaining_accounts[multisig_signers..])?;
+    require_transfer_memo(&destination.to_account_info(), extension_accounts)?;
 
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
-    let signer = ctx.accounts.authority.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
@@ -92,39 +517,126 @@ pub fn transfer_checked<'info>(
     let zero_value = as_euint128(cpi_ctx3, 0)?;
 
     let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let transfer_amount = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+    let mut transfer_amount = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+
+    if is_delegate {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let within_allowance = e_ge(cpi_ctx, source.delegated_amount, transfer_amount, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        transfer_amount = e_select(cpi_ctx, within_allowance, transfer_amount, zero_value, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        source.delegated_amount = e_sub(cpi_ctx, source.delegated_amount, transfer_amount, 0u8)?;
+    }
 
     let cpi_ctx5 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let new_source_balance = e_sub(cpi_ctx5, source.amount, transfer_amount, 0u8)?;
     source.amount = new_source_balance;
 
+    let fee_config = get_extension::<TransferFeeConfig>(&mint.to_account_info().try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?;
+    let mut credited_amount = transfer_amount;
+    let mut withheld = None;
+    if let Some(config) = fee_config {
+        let transfer_fee = config.get_epoch_fee(Clock::get()?.epoch);
+        let fee = calculate_transfer_fee(&inco, &signer, transfer_fee, transfer_amount)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        credited_amount = e_sub(cpi_ctx, transfer_amount, fee, 0u8)?;
+
+        let destination_info = destination.to_account_info();
+        let destination_withheld = withheld_amount(&destination_info)?.ok_or(CustomError::ExtensionNotFound)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let new_withheld_amount = e_add(cpi_ctx, destination_withheld, fee, 0u8)?;
+        set_extension(
+            &mut destination_info.try_borrow_mut_data()?,
+            ACCOUNT_EXTENSIONS_OFFSET,
+            &TransferFeeAmount { withheld_amount: new_withheld_amount },
+        )?;
+        withheld = Some((fee, new_withheld_amount));
+    }
+
     let cpi_ctx6 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let new_dest_balance = e_add(cpi_ctx6, destination.amount, transfer_amount, 0u8)?;
+    let new_dest_balance = e_add(cpi_ctx6, destination.amount, credited_amount, 0u8)?;
     destination.amount = new_dest_balance;
 
-    if ctx.remaining_accounts.len() >= 2 {
+    call_allow_from_remaining(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_source_balance, source.owner, 0,
+    )?;
+
+    call_allow_from_remaining(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_dest_balance, destination.owner, 2,
+    )?;
+
+    if is_delegate {
         call_allow_from_remaining(
             &inco, &signer,
             &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            new_source_balance, source.owner, 0,
+            remaining_accounts,
+            source.delegated_amount, source.owner, 4,
         )?;
-    }
-
-    if ctx.remaining_accounts.len() >= 4 {
         call_allow_from_remaining(
             &inco, &signer,
             &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            new_dest_balance, destination.owner, 2,
+            remaining_accounts,
+            source.delegated_amount, authority_key, 6,
         )?;
     }
 
+    let mut audited_handles = vec![transfer_amount, new_source_balance, new_dest_balance];
+    if is_delegate {
+        audited_handles.push(source.delegated_amount);
+    }
+    if let Some((fee, withheld_amount)) = withheld {
+        audited_handles.push(fee);
+        audited_handles.push(withheld_amount);
+    }
+    allow_auditor(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint, &audited_handles,
+    )?;
+
+    invoke_transfer_hook(
+        &source.to_account_info(),
+        &mint.to_account_info(),
+        &destination.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        extension_accounts,
+        transfer_amount,
+    )?;
+
+    emit!(TransferEvent {
+        source: source.key(),
+        destination: destination.key(),
+        authority: authority_key,
+        amount: transfer_amount,
+        new_source_balance,
+        new_destination_balance: new_dest_balance,
+        delegated_amount: source.delegated_amount,
+    });
+
+    if let Some((fee, withheld_amount)) = withheld {
+        emit!(TransferFeeWithheldEvent {
+            destination: destination.key(),
+            fee,
+            withheld_amount,
+        });
+    }
+
     Ok(())
 }
 
 /// Mint to checked - validates decimals match mint
-/// remaining_accounts:
+/// remaining_accounts (after any multisig signers):
 ///   [0] allowance_account (mut)
 ///   [1] owner_address (readonly)
 pub fn mint_to_checked<'info>(
@@ -145,13 +657,15 @@ pub fn mint_to_checked<'info>(
         COption::Some(authority) => authority,
         COption::None => return Err(CustomError::FixedSupply.into()),
     };
-    require!(mint_authority == ctx.accounts.authority.key(), CustomError::OwnerMismatch);
+    let multisig_signers = validate_owner(&mint_authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
 
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
-    let signer = ctx.accounts.authority.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
+    let requested = new_euint128(cpi_ctx, ciphertext, input_type)?;
+    let amount = capped_mint_amount(&inco, &signer, mint, requested)?;
 
     let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let new_supply = e_add(cpi_ctx2, mint.supply, amount, 0u8)?;
@@ -161,22 +675,39 @@ pub fn mint_to_checked<'info>(
     let new_balance = e_add(cpi_ctx3, account.amount, amount, 0u8)?;
     account.amount = new_balance;
 
-    if ctx.remaining_accounts.len() >= 2 {
-        call_allow_from_remaining(
-            &inco, &signer,
-            &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            new_balance, account.owner, 0,
-        )?;
-    }
+    call_allow_from_remaining(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_balance, account.owner, 0,
+    )?;
+
+    allow_auditor(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint, &[amount, new_balance, new_supply],
+    )?;
+
+    emit!(MintToEvent {
+        mint: mint.key(),
+        account: account.key(),
+        amount,
+        new_balance,
+        new_supply,
+    });
 
     Ok(())
 }
 
 /// Burn checked - validates decimals match mint
-/// remaining_accounts:
+/// remaining_accounts (after any multisig signers):
 ///   [0] allowance_account (mut)
 ///   [1] owner_address (readonly)
+///   [2] delegated_allowance_account (mut) - only when a delegate signs
+///   [3] owner_address (readonly)
+///   [4] delegated_allowance_account (mut) - only when a delegate signs
+///   [5] delegate_address (readonly)
 pub fn burn_checked<'info>(
     ctx: Context<'_, '_, '_, 'info, BurnChecked<'info>>,
     ciphertext: Vec<u8>,
@@ -191,17 +722,23 @@ pub fn burn_checked<'info>(
     require!(mint.is_initialized, CustomError::UninitializedState);
     require!(account.mint == mint.key(), CustomError::MintMismatch);
     require!(mint.decimals == decimals, CustomError::MintDecimalsMismatch);
+    require!(account.is_native.is_none(), CustomError::NativeNotSupported);
 
     let authority_key = ctx.accounts.authority.key();
-    if account.owner != authority_key {
+    // The mint's permanent delegate acts with the owner's rights
+    let is_permanent_delegate = permanent_delegate(&mint.to_account_info())? == Some(authority_key);
+    let is_delegate = account.owner != authority_key && !is_permanent_delegate;
+    if is_delegate {
         match account.delegate {
             COption::Some(delegate) if delegate == authority_key => {}
             _ => return Err(CustomError::OwnerMismatch.into()),
         }
     }
+    let multisig_signers = validate_owner(&authority_key, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
 
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
-    let signer = ctx.accounts.authority.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
@@ -213,7 +750,18 @@ pub fn burn_checked<'info>(
     let zero_value = as_euint128(cpi_ctx3, 0)?;
 
     let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let burn_amount = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+    let mut burn_amount = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+
+    if is_delegate {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let within_allowance = e_ge(cpi_ctx, account.delegated_amount, burn_amount, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        burn_amount = e_select(cpi_ctx, within_allowance, burn_amount, zero_value, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        account.delegated_amount = e_sub(cpi_ctx, account.delegated_amount, burn_amount, 0u8)?;
+    }
 
     let cpi_ctx5 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let new_balance = e_sub(cpi_ctx5, account.amount, burn_amount, 0u8)?;
@@ -223,20 +771,54 @@ pub fn burn_checked<'info>(
     let new_supply = e_sub(cpi_ctx6, mint.supply, burn_amount, 0u8)?;
     mint.supply = new_supply;
 
-    if ctx.remaining_accounts.len() >= 2 {
+    call_allow_from_remaining(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_balance, account.owner, 0,
+    )?;
+
+    if is_delegate {
+        call_allow_from_remaining(
+            &inco, &signer,
+            &ctx.accounts.system_program.to_account_info(),
+            remaining_accounts,
+            account.delegated_amount, account.owner, 2,
+        )?;
         call_allow_from_remaining(
             &inco, &signer,
             &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            new_balance, account.owner, 0,
+            remaining_accounts,
+            account.delegated_amount, authority_key, 4,
         )?;
     }
 
+    let mut audited_handles = vec![burn_amount, new_balance, new_supply];
+    if is_delegate {
+        audited_handles.push(account.delegated_amount);
+    }
+    allow_auditor(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint, &audited_handles,
+    )?;
+
+    emit!(BurnEvent {
+        account: account.key(),
+        mint: mint.key(),
+        authority: authority_key,
+        amount: burn_amount,
+        new_balance,
+        new_supply,
+        delegated_amount: account.delegated_amount,
+    });
+
     Ok(())
 }
 
 /// Approve checked - validates decimals match mint
-/// remaining_accounts:
+/// remaining_accounts (after any multisig signers):
 ///   [0] allowance_account (mut)
 ///   [1] delegate_address (readonly)
 pub fn approve_checked<'info>(
@@ -253,27 +835,39 @@ pub fn approve_checked<'info>(
     require!(source.owner == ctx.accounts.owner.key(), CustomError::OwnerMismatch);
     require!(source.mint == mint.key(), CustomError::MintMismatch);
     require!(mint.decimals == decimals, CustomError::MintDecimalsMismatch);
+    require_transferable(&mint.to_account_info())?;
+    let multisig_signers = validate_owner(&source.owner, &ctx.accounts.owner, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
 
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.owner, ctx.remaining_accounts, multisig_signers);
 
-    let cpi_ctx = CpiContext::new(
-        inco.clone(),
-        Operation { signer: ctx.accounts.owner.to_account_info() }
-    );
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
 
     source.delegate = COption::Some(ctx.accounts.delegate.key());
     source.delegated_amount = amount;
 
-    if ctx.remaining_accounts.len() >= 2 {
-        call_allow_from_remaining(
-            &inco,
-            &ctx.accounts.owner.to_account_info(),
-            &ctx.accounts.system_program.to_account_info(),
-            ctx.remaining_accounts,
-            amount, ctx.accounts.delegate.key(), 0,
-        )?;
-    }
+    call_allow_from_remaining(
+        &inco,
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        amount, ctx.accounts.delegate.key(), 0,
+    )?;
+
+    allow_auditor(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint, &[amount],
+    )?;
+
+    emit!(ApproveEvent {
+        source: source.key(),
+        delegate: ctx.accounts.delegate.key(),
+        delegated_amount: amount,
+    });
 
     Ok(())
 }
@@ -294,13 +888,28 @@ pub fn initialize_account3<'info>(ctx: Context<'_, '_, '_, 'info, InitializeAcco
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     account.amount = as_euint128(cpi_ctx, 0)?;
     account.delegate = COption::None;
-    account.state = AccountState::Initialized;
-    account.is_native = COption::None;
+    account.state = default_account_state(&mint.to_account_info())?;
+    account.is_native = native_reserve(&mint.key(), &account.to_account_info())?;
 
-    let cpi_ctx2 = CpiContext::new(inco, Operation { signer });
+    let cpi_ctx2 = CpiContext::new(inco, Operation { signer: signer.clone() });
     account.delegated_amount = as_euint128(cpi_ctx2, 0)?;
     account.close_authority = COption::None;
 
+    init_account_extensions(
+        &account.to_account_info(),
+        &mint.to_account_info(),
+        &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        account.delegated_amount,
+    )?;
+
+    emit!(InitializeAccountEvent {
+        account: account.key(),
+        mint: account.mint,
+        owner: account.owner,
+        amount: account.amount,
+    });
+
     Ok(())
 }
 
@@ -309,19 +918,30 @@ pub fn revoke_2022<'info>(ctx: Context<'_, '_, '_, 'info, Revoke2022<'info>>) ->
 
     require!(source.state == AccountState::Initialized, CustomError::UninitializedState);
     require!(source.owner == ctx.accounts.authority.key(), CustomError::OwnerMismatch);
+    let multisig_signers = validate_owner(&source.owner, &ctx.accounts.authority, ctx.remaining_accounts)?;
 
     source.delegate = COption::None;
 
     let cpi_ctx = CpiContext::new(
         ctx.accounts.inco_lightning_program.to_account_info(),
-        Operation { signer: ctx.accounts.authority.to_account_info() }
+        Operation { signer: operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers) }
     );
     source.delegated_amount = as_euint128(cpi_ctx, 0)?;
 
+    emit!(RevokeEvent {
+        source: source.key(),
+        delegated_amount: source.delegated_amount,
+    });
+
     Ok(())
 }
 
-pub fn close_account_2022<'info>(ctx: Context<'_, '_, '_, 'info, CloseAccount2022<'info>>) -> Result<()> {
+/// Close account - requires an attested zero balance, and zero withheld fees when
+/// the account holds a TransferFeeAmount, see token::close_account
+pub fn close_account_2022<'info>(
+    ctx: Context<'_, '_, '_, 'info, CloseAccount2022<'info>>,
+    balance_plaintext: Vec<u8>,
+) -> Result<()> {
     let account = &ctx.accounts.account;
 
     require!(account.state == AccountState::Initialized, CustomError::UninitializedState);
@@ -333,128 +953,1265 @@ pub fn close_account_2022<'info>(ctx: Context<'_, '_, '_, 'info, CloseAccount202
         COption::None => false,
     };
     require!(is_owner || is_close_authority, CustomError::OwnerMismatch);
-
-    let dest_starting_lamports = ctx.accounts.destination.lamports();
-    **ctx.accounts.destination.lamports.borrow_mut() = dest_starting_lamports
-        .checked_add(ctx.accounts.account.to_account_info().lamports())
-        .ok_or(CustomError::Overflow)?;
-    **ctx.accounts.account.to_account_info().lamports.borrow_mut() = 0;
+    let multisig_signers = validate_owner(&authority_key, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+    let mut handles = vec![account.amount];
+    handles.extend(withheld_amount(&account.to_account_info())?);
+    verify_zero_handles(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.instructions,
+        &operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers),
+        &handles,
+        balance_plaintext,
+    )?;
+
+    emit!(CloseEvent {
+        account: account.key(),
+        destination: ctx.accounts.destination.key(),
+    });
 
     Ok(())
 }
 
-// ========== ACCOUNT CONTEXTS ==========
+/// Close a mint - requires the mint close authority and an attested zero supply
+/// `supply_plaintext` is the attested plaintext of `mint.supply`; the data,
+/// extensions included, is wiped and the lamports go to `destination`
+pub fn close_mint<'info>(ctx: Context<'_, '_, '_, 'info, CloseMint<'info>>, supply_plaintext: Vec<u8>) -> Result<()> {
+    let mint = &ctx.accounts.mint;
 
-#[derive(Accounts)]
-pub struct TransferChecked<'info> {
-    #[account(
-        mut,
-        constraint = source.state == AccountState::Initialized @ CustomError::UninitializedState,
-        constraint = source.state != AccountState::Frozen @ CustomError::AccountFrozen,
-    )]
-    pub source: Account<'info, IncoAccount>,
-    #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
-    pub mint: Account<'info, IncoMint>,
-    #[account(
-        mut,
-        constraint = destination.state == AccountState::Initialized @ CustomError::UninitializedState,
-        constraint = destination.state != AccountState::Frozen @ CustomError::AccountFrozen,
-    )]
-    pub destination: Account<'info, IncoAccount>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
-    pub system_program: Program<'info, System>,
-}
+    let extension = get_extension::<MintCloseAuthority>(&mint.to_account_info().try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let close_authority = match extension.close_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::AuthorityTypeNotSupported.into()),
+    };
+    let multisig_signers = validate_owner(&close_authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+    let supply = verify_attested_plaintext(
+        &ctx.accounts.inco_lightning_program,
+        &ctx.accounts.instructions,
+        &operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers),
+        mint.supply,
+        supply_plaintext,
+    )?;
+    require!(supply == 0, CustomError::MintHasSupply);
+
+    // Lamports, data and ownership are released by the `close` constraint
+    emit!(CloseEvent {
+        account: mint.key(),
+        destination: ctx.accounts.destination.key(),
+    });
 
-#[derive(Accounts)]
-pub struct MintToChecked<'info> {
-    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
-    pub mint: Account<'info, IncoMint>,
-    #[account(mut, constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState)]
-    pub account: Account<'info, IncoAccount>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
-    pub system_program: Program<'info, System>,
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct BurnChecked<'info> {
-    #[account(
-        mut,
-        constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState,
-        constraint = account.state != AccountState::Frozen @ CustomError::AccountFrozen,
-    )]
-    pub account: Account<'info, IncoAccount>,
-    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
-    pub mint: Account<'info, IncoMint>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
-    pub system_program: Program<'info, System>,
-}
+// ========== TOKEN 2022 EXTENSIONS ==========
 
-#[derive(Accounts)]
-pub struct ApproveChecked<'info> {
-    #[account(
-        mut,
-        constraint = source.state == AccountState::Initialized @ CustomError::UninitializedState,
-        constraint = source.state != AccountState::Frozen @ CustomError::AccountFrozen,
-    )]
-    pub source: Account<'info, IncoAccount>,
-    #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
-    pub mint: Account<'info, IncoMint>,
-    /// CHECK: Delegate address
-    pub delegate: UncheckedAccount<'info>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
-    pub system_program: Program<'info, System>,
-}
+/// Grow an account so that the given account extensions can be initialized
+pub fn reallocate<'info>(
+    ctx: Context<'_, '_, '_, 'info, Reallocate<'info>>,
+    extension_types: Vec<ExtensionType>,
+) -> Result<()> {
+    let account = &ctx.accounts.account;
 
-#[derive(Accounts)]
-pub struct InitializeAccount3<'info> {
-    #[account(init_if_needed, payer = authority, space = 8 + IncoAccount::LEN)]
-    pub account: Account<'info, IncoAccount>,
-    #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
-    pub mint: Account<'info, IncoMint>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
+    // The rent reserve of native accounts is fixed at initialization
+    require!(account.is_native.is_none(), CustomError::NativeNotSupported);
+    require!(
+        extension_types.iter().all(|extension_type| extension_type.is_account_extension()),
+        CustomError::InvalidExtensionType
+    );
+    validate_owner(&account.owner, &ctx.accounts.owner, ctx.remaining_accounts)?;
+
+    let data_len = reallocate_extensions(
+        &account.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        ACCOUNT_EXTENSIONS_OFFSET,
+        &extension_types,
+    )?;
+
+    emit!(ReallocateEvent {
+        owned: account.key(),
+        extension_types,
+        data_len: data_len as u64,
+    });
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct Revoke2022<'info> {
-    #[account(mut, constraint = source.state == AccountState::Initialized @ CustomError::UninitializedState)]
-    pub source: Account<'info, IncoAccount>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    /// CHECK: Inco Lightning program
-    #[account(address = INCO_LIGHTNING_ID)]
-    pub inco_lightning_program: AccountInfo<'info>,
+/// Grow a mint so that the given mint extensions can be initialized
+pub fn reallocate_mint<'info>(
+    ctx: Context<'_, '_, '_, 'info, ReallocateMint<'info>>,
+    extension_types: Vec<ExtensionType>,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    require!(
+        extension_types.iter().all(|extension_type| extension_type.is_mint_extension()),
+        CustomError::InvalidExtensionType
+    );
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+
+    let data_len = reallocate_extensions(
+        &mint.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        MINT_EXTENSIONS_OFFSET,
+        &extension_types,
+    )?;
+
+    emit!(ReallocateEvent {
+        owned: mint.key(),
+        extension_types,
+        data_len: data_len as u64,
+    });
+
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct CloseAccount2022<'info> {
-    #[account(mut, constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState)]
-    pub account: Account<'info, IncoAccount>,
-    /// CHECK: Destination for lamports
-    #[account(mut)]
+/// Point the mint at its metadata account; the mint must have been reallocated for it
+pub fn initialize_metadata_pointer<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeMetadataPointer<'info>>,
+    authority: Option<Pubkey>,
+    metadata_address: Option<Pubkey>,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+
+    let pointer = MetadataPointer {
+        authority: match authority {
+            Some(authority) => COption::Some(authority),
+            None => COption::None,
+        },
+        metadata_address: match metadata_address {
+            Some(address) => COption::Some(address),
+            None => COption::None,
+        },
+    };
+    init_extension(&mut mint.to_account_info().try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &pointer)?;
+
+    emit!(MetadataPointerEvent {
+        mint: mint.key(),
+        authority,
+        metadata_address,
+    });
+
+    Ok(())
+}
+
+pub fn update_metadata_pointer<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateMetadataPointer<'info>>,
+    metadata_address: Option<Pubkey>,
+) -> Result<()> {
+    let mint_info = ctx.accounts.mint.to_account_info();
+
+    let mut pointer = get_extension::<MetadataPointer>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let pointer_authority = match pointer.authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::AuthorityTypeNotSupported.into()),
+    };
+    validate_owner(&pointer_authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+    pointer.metadata_address = match metadata_address {
+        Some(address) => COption::Some(address),
+        None => COption::None,
+    };
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &pointer)?;
+
+    emit!(MetadataPointerEvent {
+        mint: mint_info.key(),
+        authority: pointer.authority.to_option(),
+        metadata_address,
+    });
+
+    Ok(())
+}
+
+/// Set the program invoked on every transfer; the mint must have been reallocated for it
+pub fn initialize_transfer_hook<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeTransferHook<'info>>,
+    authority: Option<Pubkey>,
+    program_id: Option<Pubkey>,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+    require!(program_id != Some(crate::ID), CustomError::InvalidInstruction);
+
+    let hook = TransferHook {
+        authority: match authority {
+            Some(authority) => COption::Some(authority),
+            None => COption::None,
+        },
+        program_id: match program_id {
+            Some(program_id) => COption::Some(program_id),
+            None => COption::None,
+        },
+    };
+    init_extension(&mut mint.to_account_info().try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &hook)?;
+
+    emit!(TransferHookEvent {
+        mint: mint.key(),
+        authority,
+        program_id,
+    });
+
+    Ok(())
+}
+
+pub fn update_transfer_hook<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateTransferHook<'info>>,
+    program_id: Option<Pubkey>,
+) -> Result<()> {
+    let mint_info = ctx.accounts.mint.to_account_info();
+
+    let mut hook = get_extension::<TransferHook>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let hook_authority = match hook.authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::AuthorityTypeNotSupported.into()),
+    };
+    validate_owner(&hook_authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    require!(program_id != Some(crate::ID), CustomError::InvalidInstruction);
+
+    hook.program_id = match program_id {
+        Some(program_id) => COption::Some(program_id),
+        None => COption::None,
+    };
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &hook)?;
+
+    emit!(TransferHookEvent {
+        mint: mint_info.key(),
+        authority: hook.authority.to_option(),
+        program_id,
+    });
+
+    Ok(())
+}
+
+/// Bind the mint's tokens to the accounts holding them; the mint must have been
+/// reallocated for it. This cannot be undone.
+/// Only in the transaction that initializes the mint, so that every token
+/// account of the mint is marked NonTransferableAccount when it is initialized.
+pub fn initialize_non_transferable_mint<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeNonTransferableMint<'info>>,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    require_mint_initialization(&mint.key(), &ctx.accounts.instructions)?;
+
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+
+    init_extension(&mut mint.to_account_info().try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &NonTransferable {})?;
+
+    emit!(NonTransferableEvent { mint: mint.key() });
+
+    Ok(())
+}
+
+/// Require incoming transfers to be preceded by build_memo; the account must have been reallocated for it
+pub fn enable_required_transfer_memos<'info>(ctx: Context<'_, '_, '_, 'info, RequiredTransferMemos<'info>>) -> Result<()> {
+    set_required_transfer_memos(ctx, true)
+}
+
+pub fn disable_required_transfer_memos<'info>(ctx: Context<'_, '_, '_, 'info, RequiredTransferMemos<'info>>) -> Result<()> {
+    set_required_transfer_memos(ctx, false)
+}
+
+fn set_required_transfer_memos<'info>(
+    ctx: Context<'_, '_, '_, 'info, RequiredTransferMemos<'info>>,
+    require_incoming_transfer_memos: bool,
+) -> Result<()> {
+    let account = &ctx.accounts.account;
+    validate_owner(&account.owner, &ctx.accounts.owner, ctx.remaining_accounts)?;
+
+    let account_info = account.to_account_info();
+    let extension = MemoTransfer { require_incoming_transfer_memos };
+    if get_extension::<MemoTransfer>(&account_info.try_borrow_data()?, ACCOUNT_EXTENSIONS_OFFSET)?.is_some() {
+        set_extension(&mut account_info.try_borrow_mut_data()?, ACCOUNT_EXTENSIONS_OFFSET, &extension)?;
+    } else {
+        init_extension(&mut account_info.try_borrow_mut_data()?, ACCOUNT_EXTENSIONS_OFFSET, &extension)?;
+    }
+
+    emit!(MemoTransferEvent {
+        account: account.key(),
+        require_incoming_transfer_memos,
+    });
+
+    Ok(())
+}
+
+/// Name who may close the mint; the mint must have been reallocated for it
+/// Only in the transaction that initializes the mint, so that holders know the
+/// mint may be closed before they take any tokens.
+pub fn initialize_mint_close_authority<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeMintCloseAuthority<'info>>,
+    close_authority: Option<Pubkey>,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    require_mint_initialization(&mint.key(), &ctx.accounts.instructions)?;
+
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+
+    let extension = MintCloseAuthority {
+        close_authority: match close_authority {
+            Some(authority) => COption::Some(authority),
+            None => COption::None,
+        },
+    };
+    init_extension(&mut mint.to_account_info().try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &extension)?;
+
+    emit!(MintCloseAuthorityEvent {
+        mint: mint.key(),
+        close_authority,
+    });
+
+    Ok(())
+}
+
+pub fn update_mint_close_authority<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateMintCloseAuthority<'info>>,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    let mint_info = ctx.accounts.mint.to_account_info();
+
+    let mut extension = get_extension::<MintCloseAuthority>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let close_authority = match extension.close_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::AuthorityTypeNotSupported.into()),
+    };
+    validate_owner(&close_authority, &ctx.accounts.close_authority, ctx.remaining_accounts)?;
+
+    extension.close_authority = match new_authority {
+        Some(authority) => COption::Some(authority),
+        None => COption::None,
+    };
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &extension)?;
+
+    emit!(MintCloseAuthorityEvent {
+        mint: mint_info.key(),
+        close_authority: new_authority,
+    });
+
+    Ok(())
+}
+
+/// Accrue interest on the mint's displayed balances; the mint must have been reallocated for it
+pub fn initialize_interest_bearing_mint<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeInterestBearingMint<'info>>,
+    rate_authority: Option<Pubkey>,
+    rate: i16,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let config = InterestBearingConfig {
+        rate_authority: match rate_authority {
+            Some(authority) => COption::Some(authority),
+            None => COption::None,
+        },
+        initialization_timestamp: now,
+        pre_update_average_rate: rate,
+        last_update_timestamp: now,
+        current_rate: rate,
+    };
+    init_extension(&mut mint.to_account_info().try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &config)?;
+
+    emit!(InterestRateEvent {
+        mint: mint.key(),
+        rate,
+        pre_update_average_rate: rate,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Change the rate from now on; interest accrued so far is kept
+pub fn update_interest_rate<'info>(ctx: Context<'_, '_, '_, 'info, UpdateInterestRate<'info>>, rate: i16) -> Result<()> {
+    let mint_info = ctx.accounts.mint.to_account_info();
+
+    let mut config = get_extension::<InterestBearingConfig>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let rate_authority = match config.rate_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::AuthorityTypeNotSupported.into()),
+    };
+    validate_owner(&rate_authority, &ctx.accounts.rate_authority, ctx.remaining_accounts)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    config.pre_update_average_rate = config.time_weighted_average_rate(now).ok_or(CustomError::Overflow)?;
+    config.last_update_timestamp = now;
+    config.current_rate = rate;
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &config)?;
+
+    emit!(InterestRateEvent {
+        mint: mint_info.key(),
+        rate,
+        pre_update_average_rate: config.pre_update_average_rate,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// Scale the encrypted balance by the interest accrued up to now. The result is
+/// in base units, like the balance, and is granted to the account owner; the
+/// balance and principal are not revealed.
+/// remaining_accounts:
+///   [0] allowance_account (mut)
+///   [1] owner_address (readonly)
+pub fn amount_to_ui_amount<'info>(ctx: Context<'_, '_, '_, 'info, AmountToUiAmount<'info>>) -> Result<Euint128> {
+    let account = &ctx.accounts.account;
+    let mint = &ctx.accounts.mint;
+
+    let config = get_extension::<InterestBearingConfig>(&mint.to_account_info().try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let now = Clock::get()?.unix_timestamp;
+    let accrued_factor = config.accrued_factor(now).ok_or(CustomError::Overflow)?;
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.authority.to_account_info();
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let factor = as_euint128(cpi_ctx, accrued_factor)?;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let scaled_amount = e_mul(cpi_ctx2, account.amount, factor, 0u8)?;
+
+    let cpi_ctx3 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let factor_bits = as_euint128(cpi_ctx3, INTEREST_FACTOR_BITS as u128)?;
+
+    let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let ui_amount = e_shr(cpi_ctx4, scaled_amount, factor_bits, 0u8)?;
+
+    call_allow_from_remaining(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        ui_amount, account.owner, 0,
+    )?;
+
+    allow_auditor(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        ctx.remaining_accounts,
+        mint, &[ui_amount],
+    )?;
+
+    emit!(UiAmountEvent {
+        account: account.key(),
+        ui_amount,
+        timestamp: now,
+    });
+
+    Ok(ui_amount)
+}
+
+/// Name a delegate that may transfer or burn from any account of the mint,
+/// regardless of the account's own delegate; the mint must have been reallocated for it
+/// Only in the transaction that initializes the mint, so that holders know of
+/// the delegate before they take any tokens.
+pub fn initialize_permanent_delegate<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializePermanentDelegate<'info>>,
+    delegate: Pubkey,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    require_mint_initialization(&mint.key(), &ctx.accounts.instructions)?;
+
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+
+    let extension = PermanentDelegate { delegate: COption::Some(delegate) };
+    init_extension(&mut mint.to_account_info().try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &extension)?;
+
+    emit!(PermanentDelegateEvent {
+        mint: mint.key(),
+        delegate: Some(delegate),
+    });
+
+    Ok(())
+}
+
+/// Only the current permanent delegate can hand the role over or give it up
+pub fn update_permanent_delegate<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdatePermanentDelegate<'info>>,
+    new_delegate: Option<Pubkey>,
+) -> Result<()> {
+    let mint_info = ctx.accounts.mint.to_account_info();
+
+    let mut extension = get_extension::<PermanentDelegate>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let delegate = match extension.delegate {
+        COption::Some(delegate) => delegate,
+        COption::None => return Err(CustomError::AuthorityTypeNotSupported.into()),
+    };
+    validate_owner(&delegate, &ctx.accounts.delegate, ctx.remaining_accounts)?;
+
+    extension.delegate = match new_delegate {
+        Some(delegate) => COption::Some(delegate),
+        None => COption::None,
+    };
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &extension)?;
+
+    emit!(PermanentDelegateEvent {
+        mint: mint_info.key(),
+        delegate: new_delegate,
+    });
+
+    Ok(())
+}
+
+/// Set the state new accounts start in; the mint must have been reallocated for it
+pub fn initialize_default_account_state<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeDefaultAccountState<'info>>,
+    state: AccountState,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    require!(state != AccountState::Uninitialized, CustomError::InvalidDefaultAccountState);
+    // Frozen accounts could never be thawed without a freeze authority
+    if state == AccountState::Frozen {
+        require!(mint.freeze_authority.is_some(), CustomError::MintCannotFreeze);
+    }
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+
+    let default_state = DefaultAccountState { state: state.clone() };
+    init_extension(&mut mint.to_account_info().try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &default_state)?;
+
+    emit!(DefaultAccountStateEvent {
+        mint: mint.key(),
+        state,
+    });
+
+    Ok(())
+}
+
+/// Change the state new accounts start in; existing accounts are unaffected
+pub fn update_default_account_state<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateDefaultAccountState<'info>>,
+    state: AccountState,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+    let mint_info = mint.to_account_info();
+
+    require!(state != AccountState::Uninitialized, CustomError::InvalidDefaultAccountState);
+    let freeze_authority = match mint.freeze_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::MintCannotFreeze.into()),
+    };
+    validate_owner(&freeze_authority, &ctx.accounts.freeze_authority, ctx.remaining_accounts)?;
+
+    let mut default_state = get_extension::<DefaultAccountState>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    default_state.state = state.clone();
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &default_state)?;
+
+    emit!(DefaultAccountStateEvent {
+        mint: mint.key(),
+        state,
+    });
+
+    Ok(())
+}
+
+/// Charge a fee on transfer_checked; the mint must have been reallocated for it
+/// Only in the transaction that initializes the mint, so that every token
+/// account of the mint is given a TransferFeeAmount when it is initialized.
+/// remaining_accounts (after any multisig signers):
+///   [0] allowance_account (mut) - maximum fee
+///   [1] mint_authority_address (readonly)
+pub fn initialize_transfer_fee_config<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeTransferFeeConfig<'info>>,
+    transfer_fee_config_authority: Option<Pubkey>,
+    withdraw_withheld_authority: Option<Pubkey>,
+    transfer_fee_basis_points: u16,
+    maximum_fee: AmountInput,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+
+    require!(transfer_fee_basis_points <= MAX_FEE_BASIS_POINTS, CustomError::TransferFeeExceedsMaximum);
+    require_mint_initialization(&mint.key(), &ctx.accounts.instructions)?;
+    let mint_authority = match mint.mint_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::FixedSupply.into()),
+    };
+    let multisig_signers = validate_owner(&mint_authority, &ctx.accounts.mint_authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.mint_authority, ctx.remaining_accounts, multisig_signers);
+
+    let maximum_fee = amount_input_handle(&inco, &signer, maximum_fee)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let withheld_amount = as_euint128(cpi_ctx, 0)?;
+
+    let transfer_fee = TransferFee {
+        epoch: Clock::get()?.epoch,
+        maximum_fee,
+        transfer_fee_basis_points,
+    };
+    let config = TransferFeeConfig {
+        transfer_fee_config_authority: match transfer_fee_config_authority {
+            Some(authority) => COption::Some(authority),
+            None => COption::None,
+        },
+        withdraw_withheld_authority: match withdraw_withheld_authority {
+            Some(authority) => COption::Some(authority),
+            None => COption::None,
+        },
+        withheld_amount,
+        older_transfer_fee: transfer_fee.clone(),
+        newer_transfer_fee: transfer_fee.clone(),
+    };
+    init_extension(&mut mint.to_account_info().try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &config)?;
+
+    call_allow_from_remaining(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        maximum_fee, mint_authority, 0,
+    )?;
+
+    allow_auditor(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint, &[maximum_fee, withheld_amount],
+    )?;
+
+    emit!(TransferFeeConfigEvent {
+        mint: mint.key(),
+        epoch: transfer_fee.epoch,
+        transfer_fee_basis_points,
+        maximum_fee,
+    });
+
+    Ok(())
+}
+
+/// Schedule a new transfer fee, charged from TRANSFER_FEE_EPOCH_DELAY epochs on
+/// The fee in force keeps applying until then; a fee scheduled earlier that has
+/// not yet come into force is replaced.
+/// remaining_accounts (after any multisig signers):
+///   [0] allowance_account (mut) - maximum fee
+///   [1] transfer_fee_config_authority_address (readonly)
+pub fn set_transfer_fee<'info>(
+    ctx: Context<'_, '_, '_, 'info, SetTransferFee<'info>>,
+    transfer_fee_basis_points: u16,
+    maximum_fee: AmountInput,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+    let mint_info = mint.to_account_info();
+
+    require!(transfer_fee_basis_points <= MAX_FEE_BASIS_POINTS, CustomError::TransferFeeExceedsMaximum);
+    let mut config = get_extension::<TransferFeeConfig>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let config_authority = match config.transfer_fee_config_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::AuthorityTypeNotSupported.into()),
+    };
+    let multisig_signers = validate_owner(&config_authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
+
+    let maximum_fee = amount_input_handle(&inco, &signer, maximum_fee)?;
+    let epoch = Clock::get()?.epoch;
+    if config.newer_transfer_fee.epoch <= epoch {
+        config.older_transfer_fee = config.newer_transfer_fee.clone();
+    }
+    config.newer_transfer_fee = TransferFee {
+        epoch: epoch + TRANSFER_FEE_EPOCH_DELAY,
+        maximum_fee,
+        transfer_fee_basis_points,
+    };
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &config)?;
+
+    call_allow_from_remaining(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        maximum_fee, config_authority, 0,
+    )?;
+
+    allow_auditor(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint, &[maximum_fee],
+    )?;
+
+    emit!(TransferFeeConfigEvent {
+        mint: mint.key(),
+        epoch: config.newer_transfer_fee.epoch,
+        transfer_fee_basis_points,
+        maximum_fee,
+    });
+
+    Ok(())
+}
+
+/// Add the fees withheld on `sources` to `withheld` and reset them to `zero_value`
+/// Sources without a TransferFeeAmount are skipped.
+fn drain_withheld<'info>(
+    inco_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    mint: &Pubkey,
+    sources: &[AccountInfo<'info>],
+    mut withheld: Euint128,
+    zero_value: Euint128,
+) -> Result<Euint128> {
+    for source_info in sources {
+        require!(source_info.owner == &crate::ID, CustomError::InvalidInstruction);
+        require!(source_info.is_writable, CustomError::InvalidInstruction);
+        let source = IncoAccount::try_deserialize(&mut &source_info.try_borrow_data()?[..])?;
+        require!(source.mint == *mint, CustomError::MintMismatch);
+
+        let fee_amount = get_extension::<TransferFeeAmount>(&source_info.try_borrow_data()?, ACCOUNT_EXTENSIONS_OFFSET)?;
+        if let Some(fee_amount) = fee_amount {
+            let cpi_ctx = CpiContext::new(inco_program.clone(), Operation { signer: signer.clone() });
+            withheld = e_add(cpi_ctx, withheld, fee_amount.withheld_amount, 0u8)?;
+            set_extension(
+                &mut source_info.try_borrow_mut_data()?,
+                ACCOUNT_EXTENSIONS_OFFSET,
+                &TransferFeeAmount { withheld_amount: zero_value },
+            )?;
+        }
+    }
+    Ok(withheld)
+}
+
+/// Move the fees withheld on token accounts into the mint. Anyone may harvest.
+/// remaining_accounts: the token accounts to harvest from (mut)
+pub fn harvest_withheld_to_mint<'info>(ctx: Context<'_, '_, '_, 'info, HarvestWithheldToMint<'info>>) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+    let mint_info = mint.to_account_info();
+
+    let mut config = get_extension::<TransferFeeConfig>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.authority.to_account_info();
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero_value = as_euint128(cpi_ctx, 0)?;
+
+    let withheld_amount = drain_withheld(
+        &inco, &signer, &mint.key(), ctx.remaining_accounts, config.withheld_amount, zero_value,
+    )?;
+    config.withheld_amount = withheld_amount;
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &config)?;
+
+    emit!(HarvestWithheldEvent {
+        mint: mint.key(),
+        sources: ctx.remaining_accounts.iter().map(|source| source.key()).collect(),
+        withheld_amount,
+    });
+
+    Ok(())
+}
+
+/// Withdraw the fees withheld on the mint and on `num_sources` token accounts
+/// remaining_accounts (after any multisig signers):
+///   [0..num_sources] token accounts to withdraw from (mut)
+///   [num_sources] allowance_account (mut) - new destination balance
+///   [num_sources + 1] destination_owner_address (readonly)
+pub fn withdraw_withheld_tokens<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawWithheldTokens<'info>>,
+    num_sources: u8,
+) -> Result<()> {
+    let mint = &ctx.accounts.mint;
+    let mint_info = mint.to_account_info();
+    let destination = &mut ctx.accounts.destination;
+
+    require!(destination.state != AccountState::Frozen, CustomError::AccountFrozen);
+    let mut config = get_extension::<TransferFeeConfig>(&mint_info.try_borrow_data()?, MINT_EXTENSIONS_OFFSET)?
+        .ok_or(CustomError::ExtensionNotFound)?;
+    let withdraw_authority = match config.withdraw_withheld_authority {
+        COption::Some(authority) => authority,
+        COption::None => return Err(CustomError::AuthorityTypeNotSupported.into()),
+    };
+    let multisig_signers = validate_owner(&withdraw_authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+    let num_sources = num_sources as usize;
+    require!(remaining_accounts.len() >= num_sources, CustomError::InvalidInstruction);
+    let (sources, remaining_accounts) = remaining_accounts.split_at(num_sources);
+    require!(
+        sources.iter().all(|source| source.key() != destination.key()),
+        CustomError::InvalidInstruction
+    );
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.authority, ctx.remaining_accounts, multisig_signers);
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero_value = as_euint128(cpi_ctx, 0)?;
+
+    let amount = drain_withheld(&inco, &signer, &mint.key(), sources, config.withheld_amount, zero_value)?;
+    config.withheld_amount = zero_value;
+    set_extension(&mut mint_info.try_borrow_mut_data()?, MINT_EXTENSIONS_OFFSET, &config)?;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_balance = e_add(cpi_ctx2, destination.amount, amount, 0u8)?;
+    destination.amount = new_balance;
+
+    call_allow_from_remaining(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        new_balance, destination.owner, 0,
+    )?;
+
+    allow_auditor(
+        &inco, &signer,
+        &ctx.accounts.system_program.to_account_info(),
+        remaining_accounts,
+        mint, &[amount, new_balance],
+    )?;
+
+    emit!(WithdrawWithheldEvent {
+        mint: mint.key(),
+        destination: destination.key(),
+        amount,
+        new_destination_balance: new_balance,
+    });
+
+    Ok(())
+}
+
+// ========== ACCOUNT CONTEXTS ==========
+
+#[derive(Accounts)]
+pub struct TransferChecked<'info> {
+    #[account(
+        mut,
+        constraint = source.state == AccountState::Initialized @ CustomError::UninitializedState,
+        constraint = source.state != AccountState::Frozen @ CustomError::AccountFrozen,
+    )]
+    pub source: Account<'info, IncoAccount>,
+    #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    #[account(
+        mut,
+        constraint = destination.state == AccountState::Initialized @ CustomError::UninitializedState,
+        constraint = destination.state != AccountState::Frozen @ CustomError::AccountFrozen,
+    )]
+    pub destination: Account<'info, IncoAccount>,
+    /// CHECK: Owner or delegate, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintToChecked<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    #[account(mut, constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState)]
+    pub account: Account<'info, IncoAccount>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BurnChecked<'info> {
+    #[account(
+        mut,
+        constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState,
+        constraint = account.state != AccountState::Frozen @ CustomError::AccountFrozen,
+    )]
+    pub account: Account<'info, IncoAccount>,
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Owner or delegate, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveChecked<'info> {
+    #[account(
+        mut,
+        constraint = source.state == AccountState::Initialized @ CustomError::UninitializedState,
+        constraint = source.state != AccountState::Frozen @ CustomError::AccountFrozen,
+    )]
+    pub source: Account<'info, IncoAccount>,
+    #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Delegate address
+    pub delegate: UncheckedAccount<'info>,
+    /// CHECK: Account owner, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeAccount3<'info> {
+    #[account(init_if_needed, payer = authority, space = 8 + IncoAccount::LEN)]
+    pub account: Account<'info, IncoAccount>,
+    #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Revoke2022<'info> {
+    #[account(mut, constraint = source.state == AccountState::Initialized @ CustomError::UninitializedState)]
+    pub source: Account<'info, IncoAccount>,
+    /// CHECK: Account owner, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAccount2022<'info> {
+    #[account(
+        mut,
+        close = destination,
+        constraint = account.state == AccountState::Initialized @ CustomError::UninitializedState,
+    )]
+    pub account: Account<'info, IncoAccount>,
+    /// CHECK: Destination for lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+    /// CHECK: Owner or close authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar holding the Ed25519 decryption attestation
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseMint<'info> {
+    #[account(
+        mut,
+        close = destination,
+        constraint = mint.is_initialized @ CustomError::UninitializedState,
+    )]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Destination for lamports
+    #[account(mut)]
     pub destination: AccountInfo<'info>,
+    /// CHECK: Mint close authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar holding the Ed25519 decryption attestation
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Reallocate<'info> {
+    #[account(
+        mut,
+        constraint = account.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+    )]
+    pub account: Account<'info, IncoAccount>,
+    /// CHECK: Account owner, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReallocateMint<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMetadataPointer<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadataPointer<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Metadata pointer authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTransferHook<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTransferHook<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Transfer hook authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeNonTransferableMint<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RequiredTransferMemos<'info> {
+    #[account(
+        mut,
+        constraint = account.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+    )]
+    pub account: Account<'info, IncoAccount>,
+    /// CHECK: Account owner, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeMintCloseAuthority<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintCloseAuthority<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Current mint close authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub close_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeInterestBearingMint<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateInterestRate<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Rate authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub rate_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AmountToUiAmount<'info> {
+    #[account(
+        constraint = account.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+        constraint = account.mint == mint.key() @ CustomError::MintMismatch,
+    )]
+    pub account: Account<'info, IncoAccount>,
+    #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePermanentDelegate<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: Instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePermanentDelegate<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Current permanent delegate, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub delegate: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDefaultAccountState<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateDefaultAccountState<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Freeze authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub freeze_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeTransferFeeConfig<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Mint authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub mint_authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetTransferFee<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Transfer fee config authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct HarvestWithheldToMint<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
     #[account(mut)]
     pub authority: Signer<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithheldTokens<'info> {
+    #[account(mut, constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    #[account(
+        mut,
+        constraint = destination.state == AccountState::Initialized @ CustomError::UninitializedState,
+        constraint = destination.mint == mint.key() @ CustomError::MintMismatch,
+    )]
+    pub destination: Account<'info, IncoAccount>,
+    /// CHECK: Withdraw withheld authority, validated in the instruction (single key or IncoMultisig)
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Clone)]
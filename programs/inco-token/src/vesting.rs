@@ -0,0 +1,348 @@
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::{e_add, e_ge, e_mul, e_select, e_shr, e_sub, new_euint128, as_euint128};
+use inco_lightning::types::Euint128;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::events::*;
+use crate::allowance::{allow_auditor, call_allow_from_remaining};
+use crate::token::{is_native_mint, operation_signer, validate_owner};
+use crate::token_2022::require_plain_transfers;
+use crate::{AccountState, COption, CustomError, IncoAccount, IncoMint};
+
+/// Seed prefix of vesting PDAs: [VESTING_SEED, grantor, seed]
+pub const VESTING_SEED: &[u8] = b"vesting";
+/// Seed prefix of vesting vault PDAs: [VESTING_VAULT_SEED, vesting]
+pub const VESTING_VAULT_SEED: &[u8] = b"vesting_vault";
+/// Fractional bits of the unlocked share; Inco Lightning has no encrypted division
+pub const UNLOCKED_FRACTION_BITS: u32 = 64;
+
+/// Unlock schedule, in unix timestamps and seconds
+/// Nothing unlocks before `cliff`. From then on the grant unlocks in equal steps
+/// every `period` seconds counted from `start`, and fully at `end`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+    pub period: i64,
+}
+
+impl VestingSchedule {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.period > 0, IncoVestingError::InvalidSchedule);
+        require!(self.start < self.end, IncoVestingError::InvalidSchedule);
+        require!(self.start <= self.cliff && self.cliff <= self.end, IncoVestingError::InvalidSchedule);
+        let duration = self.end.checked_sub(self.start).ok_or(IncoVestingError::InvalidSchedule)?;
+        require!(duration % self.period == 0, IncoVestingError::InvalidSchedule);
+        Ok(())
+    }
+
+    /// Only meaningful for a schedule that passed validate
+    pub fn total_periods(&self) -> u128 {
+        ((self.end - self.start) / self.period) as u128
+    }
+
+    /// Whole periods elapsed at `now`, capped at total_periods
+    pub fn elapsed_periods(&self, now: i64) -> u128 {
+        if now < self.cliff {
+            return 0;
+        }
+        // now >= cliff >= start, so this only saturates past the end
+        ((now.saturating_sub(self.start) / self.period) as u128).min(self.total_periods())
+    }
+
+    /// Unlocked share of the grant at `now`, with UNLOCKED_FRACTION_BITS fractional bits
+    /// Rounded up, so `(total * fraction) >> UNLOCKED_FRACTION_BITS` equals
+    /// `total * elapsed / periods` rounded down for any total below 2^64 / periods
+    pub fn unlocked_fraction(&self, now: i64) -> u128 {
+        (self.elapsed_periods(now) << UNLOCKED_FRACTION_BITS).div_ceil(self.total_periods())
+    }
+}
+
+// ========== VESTING INSTRUCTIONS ==========
+
+/// Lock an encrypted amount from the grantor's account into a vesting vault
+/// The lock is all or nothing: when the grantor's balance is too low the grant is zero.
+/// remaining_accounts (after any multisig signers):
+///   [0] allowance_account (mut) - new grantor source balance
+///   [1] grantor_address (readonly)
+///   [2] allowance_account (mut) - granted total
+///   [3] grantor_address (readonly)
+///   [4] allowance_account (mut) - granted total
+///   [5] beneficiary_address (readonly)
+pub fn create_vesting<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateVesting<'info>>,
+    seed: u64,
+    ciphertext: Vec<u8>,
+    input_type: u8,
+    schedule: VestingSchedule,
+) -> Result<()> {
+    schedule.validate()?;
+
+    let source = &mut ctx.accounts.source;
+    let vault = &mut ctx.accounts.vault;
+    let vesting = &mut ctx.accounts.vesting;
+
+    require!(source.state != AccountState::Frozen, CustomError::AccountFrozen);
+    require!(!is_native_mint(&ctx.accounts.mint.key()), CustomError::NativeNotSupported);
+    require_plain_transfers(&ctx.accounts.mint.to_account_info())?;
+
+    let grantor = ctx.accounts.grantor.key();
+    let multisig_signers = validate_owner(&grantor, &ctx.accounts.grantor, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.grantor, ctx.remaining_accounts, multisig_signers);
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let amount = new_euint128(cpi_ctx, ciphertext, input_type)?;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let has_sufficient = e_ge(cpi_ctx2, source.amount, amount, 0u8)?;
+
+    let cpi_ctx3 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero_value = as_euint128(cpi_ctx3, 0)?;
+
+    let cpi_ctx4 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let total = e_select(cpi_ctx4, has_sufficient, amount, zero_value, 0u8)?;
+
+    let cpi_ctx5 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_source_balance = e_sub(cpi_ctx5, source.amount, total, 0u8)?;
+    source.amount = new_source_balance;
+
+    vault.mint = ctx.accounts.mint.key();
+    vault.owner = vesting.key();
+    vault.amount = total;
+    vault.delegate = COption::None;
+    vault.state = AccountState::Initialized;
+    vault.is_native = COption::None;
+    vault.delegated_amount = zero_value;
+    vault.close_authority = COption::None;
+
+    vesting.grantor = grantor;
+    vesting.beneficiary = ctx.accounts.beneficiary.key();
+    vesting.seed = seed;
+    vesting.mint = ctx.accounts.mint.key();
+    vesting.vault = vault.key();
+    vesting.total = total;
+    vesting.claimed = zero_value;
+    vesting.schedule = schedule;
+    vesting.bump = ctx.bumps.vesting;
+
+    let system_program = ctx.accounts.system_program.to_account_info();
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, new_source_balance, source.owner, 0)?;
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, total, grantor, 2)?;
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, total, vesting.beneficiary, 4)?;
+
+    allow_auditor(
+        &inco, &signer, &system_program, remaining_accounts,
+        &ctx.accounts.mint, &[amount, total, new_source_balance],
+    )?;
+
+    emit!(VestingCreatedEvent {
+        vesting: vesting.key(),
+        grantor,
+        beneficiary: vesting.beneficiary,
+        mint: vesting.mint,
+        total,
+        start: schedule.start,
+        cliff: schedule.cliff,
+        end: schedule.end,
+        period: schedule.period,
+    });
+
+    Ok(())
+}
+
+/// Move the unlocked but unclaimed part of a grant to `destination`
+/// unlocked = total * elapsed_periods / total_periods, computed on the encrypted total.
+/// The claim at or after `end` releases the rest and closes the vesting and vault,
+/// returning their rent to the grantor.
+/// remaining_accounts (after any multisig signers):
+///   [0] allowance_account (mut) - new destination balance
+///   [1] destination_owner (readonly)
+///   [2] allowance_account (mut) - new claimed total
+///   [3] beneficiary_address (readonly)
+pub fn claim<'info>(ctx: Context<'_, '_, '_, 'info, Claim<'info>>) -> Result<()> {
+    let vesting = &mut ctx.accounts.vesting;
+    let vault = &mut ctx.accounts.vault;
+    let destination = &mut ctx.accounts.destination;
+
+    require!(destination.state != AccountState::Frozen, CustomError::AccountFrozen);
+    require!(vault.state != AccountState::Frozen, CustomError::AccountFrozen);
+    // The mint may have gained extensions since the grant was made
+    require_plain_transfers(&ctx.accounts.mint.to_account_info())?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let schedule = vesting.schedule;
+    require!(now >= schedule.cliff, IncoVestingError::CliffNotReached);
+    let is_complete = now >= schedule.end;
+
+    let beneficiary = vesting.beneficiary;
+    let multisig_signers = validate_owner(&beneficiary, &ctx.accounts.beneficiary, ctx.remaining_accounts)?;
+    let remaining_accounts = &ctx.remaining_accounts[multisig_signers..];
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = operation_signer(&ctx.accounts.beneficiary, ctx.remaining_accounts, multisig_signers);
+
+    let unlocked = if is_complete {
+        vesting.total
+    } else {
+        // total * elapsed / periods, as a fixed-point multiplication and shift
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let fraction = as_euint128(cpi_ctx, schedule.unlocked_fraction(now))?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let fraction_bits = as_euint128(cpi_ctx, UNLOCKED_FRACTION_BITS as u128)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let scaled = e_mul(cpi_ctx, vesting.total, fraction, 0u8)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        e_shr(cpi_ctx, scaled, fraction_bits, 0u8)?
+    };
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let claimable = e_sub(cpi_ctx, unlocked, vesting.claimed, 0u8)?;
+    vesting.claimed = unlocked;
+
+    let cpi_ctx2 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_vault_balance = e_sub(cpi_ctx2, vault.amount, claimable, 0u8)?;
+    vault.amount = new_vault_balance;
+
+    let cpi_ctx3 = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let new_dest_balance = e_add(cpi_ctx3, destination.amount, claimable, 0u8)?;
+    destination.amount = new_dest_balance;
+
+    let system_program = ctx.accounts.system_program.to_account_info();
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, new_dest_balance, destination.owner, 0)?;
+    call_allow_from_remaining(&inco, &signer, &system_program, remaining_accounts, unlocked, beneficiary, 2)?;
+
+    allow_auditor(
+        &inco, &signer, &system_program, remaining_accounts,
+        &ctx.accounts.mint, &[claimable, unlocked, new_vault_balance, new_dest_balance],
+    )?;
+
+    emit!(VestingClaimEvent {
+        vesting: vesting.key(),
+        destination: destination.key(),
+        amount: claimable,
+        claimed: unlocked,
+        is_complete,
+    });
+
+    // Everything has been released, so the vault is empty
+    if is_complete {
+        let grantor = ctx.accounts.grantor.to_account_info();
+        ctx.accounts.vault.close(grantor.clone())?;
+        ctx.accounts.vesting.close(grantor)?;
+    }
+
+    Ok(())
+}
+
+// ========== ACCOUNT STRUCTURES ==========
+
+#[account]
+pub struct IncoVesting {
+    pub grantor: Pubkey,
+    pub beneficiary: Pubkey,
+    pub seed: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub total: Euint128,
+    pub claimed: Euint128,
+    pub schedule: VestingSchedule,
+    pub bump: u8,
+}
+
+impl IncoVesting {
+    pub const LEN: usize = 32 + 32 + 8 + 32 + 32 + 32 + 32 + 32 + 1; // 233 bytes
+}
+
+// ========== ACCOUNT CONTEXTS ==========
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct CreateVesting<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + IncoVesting::LEN,
+        seeds = [VESTING_SEED, grantor.key().as_ref(), &seed.to_le_bytes()],
+        bump,
+    )]
+    pub vesting: Account<'info, IncoVesting>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + IncoAccount::LEN,
+        seeds = [VESTING_VAULT_SEED, vesting.key().as_ref()],
+        bump,
+    )]
+    pub vault: Account<'info, IncoAccount>,
+    #[account(
+        mut,
+        constraint = source.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+        constraint = source.mint == mint.key() @ CustomError::MintMismatch,
+        constraint = source.owner == grantor.key() @ CustomError::OwnerMismatch,
+    )]
+    pub source: Account<'info, IncoAccount>,
+    #[account(constraint = mint.is_initialized @ CustomError::UninitializedState)]
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Beneficiary address
+    pub beneficiary: UncheckedAccount<'info>,
+    /// CHECK: Grantor, owner of source (single key or IncoMultisig)
+    #[account(mut)]
+    pub grantor: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(
+        mut,
+        seeds = [VESTING_SEED, vesting.grantor.as_ref(), &vesting.seed.to_le_bytes()],
+        bump = vesting.bump,
+        has_one = grantor,
+        has_one = vault,
+        has_one = mint,
+    )]
+    pub vesting: Account<'info, IncoVesting>,
+    #[account(mut)]
+    pub vault: Account<'info, IncoAccount>,
+    #[account(
+        mut,
+        constraint = destination.state != AccountState::Uninitialized @ CustomError::UninitializedState,
+        constraint = destination.mint == mint.key() @ CustomError::MintMismatch,
+        constraint = destination.key() != vault.key() @ IncoVestingError::InvalidDestination,
+    )]
+    pub destination: Account<'info, IncoAccount>,
+    pub mint: Account<'info, IncoMint>,
+    /// CHECK: Beneficiary, validated against the vesting (single key or IncoMultisig)
+    #[account(mut)]
+    pub beneficiary: UncheckedAccount<'info>,
+    /// CHECK: Grantor, receives the rent when the grant is fully claimed
+    #[account(mut)]
+    pub grantor: UncheckedAccount<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// ========== ERROR CODES ==========
+#[error_code]
+pub enum IncoVestingError {
+    #[msg("Invalid vesting schedule")]
+    InvalidSchedule,
+    #[msg("Vesting cliff not reached")]
+    CliffNotReached,
+    #[msg("Claim destination cannot be the vesting vault")]
+    InvalidDestination,
+}
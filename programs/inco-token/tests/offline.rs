@@ -0,0 +1,1636 @@
+//! Offline harness: runs inco_token against the plaintext mock of Inco Lightning
+//! in tests/mock-inco-lightning, where every handle equals its plaintext.
+//!
+//! Build both programs first:
+//!   anchor build
+//!   cargo build-sbf --manifest-path tests/mock-inco-lightning/Cargo.toml
+//! then run `cargo test -p inco-token --features client --test offline`.
+
+// Every helper hands back LiteSVM's own TransactionResult
+#![allow(clippy::result_large_err)]
+
+use std::path::Path;
+
+use inco_lightning::types::Euint128;
+use inco_token::client::{self, AllowanceGrant, Authority};
+use inco_token::{
+    extra_account_metas_address, mint_space, AccountState, AuthorityType, COption, Collection, CollectionDetailsToggle,
+    CollectionToggle, CreateMasterEditionArgs, CreateMetadataArgs, Creator, ExtensionType, IncoAccount, IncoEscrow,
+    IncoMint, InterestBearingConfig, AmountInput, ExtraAccountMeta, MetadataPointer, PrintEditionArgs, TransferFeeAmount,
+    TransferFeeConfig, UpdateMetadataArgs, UsesToggle, VestingSchedule, EXTRA_ACCOUNT_FIXED, EXTRA_ACCOUNT_HOOK_PDA,
+    SEED_ACCOUNT_KEY, SEED_LITERAL,
+};
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use solana_sdk::account::Account;
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+
+/// tests/mock-transfer-hook, which rejects transfers above 100
+const MOCK_TRANSFER_HOOK_ID: Pubkey = solana_sdk::pubkey!("4gYFE9BuUL3gXb8VNztp1eSRvyfzw6DN4sbCEE2Aj9wA");
+
+struct Harness {
+    svm: LiteSVM,
+    payer: Keypair,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let deploy = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy");
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(inco_token::ID, deploy.join("inco_token.so"))
+            .expect("inco_token.so not found, run `anchor build`");
+        svm.add_program_from_file(inco_lightning::ID, deploy.join("mock_inco_lightning.so"))
+            .expect("mock_inco_lightning.so not found, run `cargo build-sbf --manifest-path tests/mock-inco-lightning/Cargo.toml`");
+        svm.add_program_from_file(MOCK_TRANSFER_HOOK_ID, deploy.join("mock_transfer_hook.so"))
+            .expect("mock_transfer_hook.so not found, run `cargo build-sbf --manifest-path tests/mock-transfer-hook/Cargo.toml`");
+
+        let payer = Keypair::new();
+        svm.airdrop(&payer.pubkey(), 100 * LAMPORTS_PER_SOL).unwrap();
+        Self { svm, payer }
+    }
+
+    fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend(signers.iter().filter(|signer| signer.pubkey() != self.payer.pubkey()));
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(tx);
+        // Identical instructions sent twice would otherwise share a signature
+        self.svm.expire_blockhash();
+        result
+    }
+
+    fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    fn authority(&self) -> Authority {
+        Authority::Single(self.payer())
+    }
+
+    fn mint(&self, mint: &Pubkey) -> IncoMint {
+        client::decode_mint(&self.svm.get_account(mint).unwrap().data).unwrap()
+    }
+
+    fn account(&self, account: &Pubkey) -> IncoAccount {
+        client::decode_account(&self.svm.get_account(account).unwrap().data).unwrap()
+    }
+
+    fn balance(&self, account: &Pubkey) -> u128 {
+        self.account(account).amount.0
+    }
+
+    fn supply(&self, mint: &Pubkey) -> u128 {
+        self.mint(mint).supply.0
+    }
+
+    fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm.get_account(address).map_or(0, |account| account.lamports)
+    }
+
+    /// Mint with the payer as mint and freeze authority
+    fn create_mint(&mut self, decimals: u8) -> Pubkey {
+        let mint = Keypair::new();
+        let ix = client::initialize_mint(mint.pubkey(), self.payer(), decimals, self.payer(), Some(self.payer()));
+        self.send(&[ix], &[&mint]).unwrap();
+        mint.pubkey()
+    }
+
+    /// Mint like create_mint, with the mint extensions `initializers` returns set
+    /// up in the transaction that initializes it
+    fn create_mint_with_extensions(
+        &mut self,
+        decimals: u8,
+        extension_types: Vec<ExtensionType>,
+        initializers: impl FnOnce(Pubkey) -> Vec<Instruction>,
+    ) -> Pubkey {
+        let mint = Keypair::new();
+        let mut instructions = vec![
+            client::initialize_mint(mint.pubkey(), self.payer(), decimals, self.payer(), Some(self.payer())),
+            client::reallocate_mint(mint.pubkey(), &self.authority(), self.payer(), extension_types),
+        ];
+        instructions.extend(initializers(mint.pubkey()));
+        self.send(&instructions, &[&mint]).unwrap();
+        mint.pubkey()
+    }
+
+    fn create_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let ix = client::initialize_account(account.pubkey(), *mint, *owner, self.payer());
+        self.send(&[ix], &[&account]).unwrap();
+        account.pubkey()
+    }
+
+    /// Keypair with lamports to pay for the allowance accounts it creates
+    fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        self.svm.airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        keypair
+    }
+
+    /// Allowance for the owner of `account` on a balance handle, which the mock sets to `balance`
+    fn grant(&self, account: &Pubkey, balance: u128) -> AllowanceGrant {
+        AllowanceGrant::new(balance, self.account(account).owner)
+    }
+
+    fn mint_to(&mut self, mint: &Pubkey, account: &Pubkey, amount: u128) {
+        let supply = self.supply(mint);
+        let minted = match self.mint(mint).max_supply {
+            COption::Some(max_supply) if supply + amount > max_supply.0 => 0,
+            _ => amount,
+        };
+        let grants = [self.grant(account, self.balance(account) + minted)];
+        let ix = client::mint_to(*mint, *account, &self.authority(), ciphertext(amount), 0, &grants);
+        self.send(&[ix], &[]).unwrap();
+    }
+
+    fn transfer(&mut self, mint: &Pubkey, source: &Pubkey, destination: &Pubkey, amount: u128) -> TransactionResult {
+        let (source_balance, destination_balance) = (self.balance(source), self.balance(destination));
+        let moved = if source_balance >= amount { amount } else { 0 };
+        let grants = [self.grant(source, source_balance - moved), self.grant(destination, destination_balance + moved)];
+        let ix = client::transfer(*source, *destination, *mint, &self.authority(), ciphertext(amount), 0, &grants);
+        self.send(&[ix], &[])
+    }
+}
+
+/// The mock reads ciphertexts as little-endian plaintexts
+fn ciphertext(amount: u128) -> Vec<u8> {
+    amount.to_le_bytes().to_vec()
+}
+
+// ========== TOKEN INSTRUCTIONS ==========
+
+#[test]
+fn initialize_mint_and_account_start_at_zero() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+
+    let mint_state = h.mint(&mint);
+    assert!(mint_state.is_initialized);
+    assert_eq!(mint_state.decimals, 9);
+    assert_eq!(mint_state.supply.0, 0);
+    assert_eq!(mint_state.mint_authority, COption::Some(h.payer()));
+
+    let account_state = h.account(&account);
+    assert!(account_state.state == AccountState::Initialized);
+    assert_eq!(account_state.mint, mint);
+    assert_eq!(account_state.amount.0, 0);
+}
+
+#[test]
+fn mint_to_credits_balance_and_supply() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+
+    h.mint_to(&mint, &account, 1_000);
+    h.mint_to(&mint, &account, 500);
+
+    assert_eq!(h.balance(&account), 1_500);
+    assert_eq!(h.supply(&mint), 1_500);
+}
+
+#[test]
+fn transfer_moves_exact_amount_and_overdraft_moves_zero() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let source = h.create_account(&mint, &h.payer());
+    let destination = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 100);
+
+    h.transfer(&mint, &source, &destination, 40).unwrap();
+    assert_eq!(h.balance(&source), 60);
+    assert_eq!(h.balance(&destination), 40);
+
+    h.transfer(&mint, &source, &destination, 61).unwrap();
+    assert_eq!(h.balance(&source), 60);
+    assert_eq!(h.balance(&destination), 40);
+}
+
+#[test]
+fn transfer_grants_allowances_to_both_owners() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let recipient = Keypair::new();
+    let source = h.create_account(&mint, &h.payer());
+    let destination = h.create_account(&mint, &recipient.pubkey());
+    h.mint_to(&mint, &source, 100);
+
+    let grants = [
+        AllowanceGrant::new(75, h.payer()),
+        AllowanceGrant::new(25, recipient.pubkey()),
+    ];
+    let ix = client::transfer(source, destination, mint, &h.authority(), ciphertext(25), 0, &grants);
+    h.send(&[ix], &[]).unwrap();
+
+    for grant in grants {
+        assert!(h.svm.get_account(&client::allowance_address(Euint128(grant.handle), &grant.allowed)).is_some());
+    }
+
+    // An allowance PDA for the wrong handle is rejected
+    let wrong = [AllowanceGrant::new(1, h.payer())];
+    let ix = client::transfer(source, destination, mint, &h.authority(), ciphertext(25), 0, &wrong);
+    assert!(h.send(&[ix], &[]).is_err());
+}
+
+#[test]
+fn transfer_batch_checks_running_balance() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let source = h.create_account(&mint, &h.payer());
+    let first = h.create_account(&mint, &h.payer());
+    let second = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 100);
+
+    let legs = vec![(first, ciphertext(30)), (second, ciphertext(50)), (first, ciphertext(40))];
+    // Source balance, then each leg's new destination balance
+    let grants = [
+        AllowanceGrant::new(20, h.payer()),
+        AllowanceGrant::new(30, h.payer()),
+        AllowanceGrant::new(50, h.payer()),
+        AllowanceGrant::new(70, h.payer()),
+    ];
+    let ix = client::transfer_batch(source, mint, &h.authority(), legs, 0, &grants);
+    h.send(&[ix], &[]).unwrap();
+
+    assert_eq!(h.balance(&source), 20);
+    assert_eq!(h.balance(&first), 30);
+    assert_eq!(h.balance(&second), 50);
+}
+
+#[test]
+fn delegate_spends_only_its_allowance() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let delegate = h.funded_keypair();
+    let source = h.create_account(&mint, &h.payer());
+    let destination = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 100);
+
+    let grants = [AllowanceGrant::new(40, delegate.pubkey())];
+    let ix = client::approve(source, mint, delegate.pubkey(), &h.authority(), ciphertext(40), 0, &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.account(&source).delegated_amount.0, 40);
+
+    // Both balances, then the remaining allowance for the owner and the delegate
+    let delegated_grants = |h: &Harness, source_balance, destination_balance, delegated| {
+        [
+            AllowanceGrant::new(source_balance, h.payer()),
+            AllowanceGrant::new(destination_balance, h.payer()),
+            AllowanceGrant::new(delegated, h.payer()),
+            AllowanceGrant::new(delegated, delegate.pubkey()),
+        ]
+    };
+    let as_delegate = Authority::Single(delegate.pubkey());
+    let grants = delegated_grants(&h, 100, 0, 40);
+    let ix = client::transfer(source, destination, mint, &as_delegate, ciphertext(50), 0, &grants);
+    h.send(&[ix], &[&delegate]).unwrap();
+    assert_eq!(h.balance(&destination), 0);
+
+    let grants = delegated_grants(&h, 70, 30, 10);
+    let ix = client::transfer(source, destination, mint, &as_delegate, ciphertext(30), 0, &grants);
+    h.send(&[ix], &[&delegate]).unwrap();
+    assert_eq!(h.balance(&source), 70);
+    assert_eq!(h.balance(&destination), 30);
+    assert_eq!(h.account(&source).delegated_amount.0, 10);
+
+    let grants = [
+        AllowanceGrant::new(60, h.payer()),
+        AllowanceGrant::new(0, h.payer()),
+        AllowanceGrant::new(0, delegate.pubkey()),
+    ];
+    let ix = client::burn(source, mint, &as_delegate, ciphertext(10), 0, &grants);
+    h.send(&[ix], &[&delegate]).unwrap();
+    assert_eq!(h.balance(&source), 60);
+    assert_eq!(h.account(&source).delegated_amount.0, 0);
+
+    let ix = client::revoke(source, &h.authority());
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.account(&source).delegate, COption::None);
+    let ix = client::transfer(source, destination, mint, &as_delegate, ciphertext(1), 0, &[]);
+    assert!(h.send(&[ix], &[&delegate]).is_err());
+}
+
+#[test]
+fn burn_reduces_balance_and_supply() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &account, 100);
+
+    let grants = [h.grant(&account, 70)];
+    let ix = client::burn(account, mint, &h.authority(), ciphertext(30), 0, &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&account), 70);
+    assert_eq!(h.supply(&mint), 70);
+
+    let ix = client::burn(account, mint, &h.authority(), ciphertext(71), 0, &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&account), 70);
+    assert_eq!(h.supply(&mint), 70);
+}
+
+#[test]
+fn frozen_account_rejects_transfers_until_thawed() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let source = h.create_account(&mint, &h.payer());
+    let destination = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 100);
+
+    let ix = client::freeze_account(source, mint, &h.authority());
+    h.send(&[ix], &[]).unwrap();
+    assert!(h.account(&source).state == AccountState::Frozen);
+    assert!(h.transfer(&mint, &source, &destination, 10).is_err());
+
+    let ix = client::thaw_account(source, mint, &h.authority());
+    h.send(&[ix], &[]).unwrap();
+    h.transfer(&mint, &source, &destination, 10).unwrap();
+    assert_eq!(h.balance(&destination), 10);
+}
+
+#[test]
+fn close_account_requires_attested_zero_balance() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+    let other = h.create_account(&mint, &h.payer());
+    let destination = Pubkey::new_unique();
+    h.mint_to(&mint, &account, 10);
+
+    // A false attestation and a non-zero balance are both rejected
+    let ix = client::close_account(account, destination, &h.authority(), 0);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::close_account(account, destination, &h.authority(), 10);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    h.transfer(&mint, &account, &other, 10).unwrap();
+    let rent = h.lamports(&account);
+    let ix = client::close_account(account, destination, &h.authority(), 0);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.lamports(&account), 0);
+    assert_eq!(h.lamports(&destination), rent);
+}
+
+#[test]
+fn set_authority_instructions_update_authorities() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+    let new_authority = h.funded_keypair();
+
+    let ix = client::set_authority(mint, &h.authority(), AuthorityType::FreezeAccount, None);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.mint(&mint).freeze_authority, COption::None);
+
+    let ix = client::set_close_authority(account, &h.authority(), Some(new_authority.pubkey()));
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.account(&account).close_authority, COption::Some(new_authority.pubkey()));
+
+    let ix = client::set_mint_authority(mint, &h.authority(), Some(new_authority.pubkey()));
+    h.send(&[ix], &[]).unwrap();
+    let grants = [h.grant(&account, 1)];
+    let ix = client::mint_to(mint, account, &h.authority(), ciphertext(1), 0, &grants);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::mint_to(mint, account, &Authority::Single(new_authority.pubkey()), ciphertext(1), 0, &grants);
+    h.send(&[ix], &[&new_authority]).unwrap();
+    assert_eq!(h.balance(&account), 1);
+
+    let ix = client::set_freeze_authority(mint, &h.authority(), Some(h.payer()));
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let ix = client::approve(account, mint, h.payer(), &h.authority(), ciphertext(1), 0, &grants);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::set_account_owner(account, &h.authority(), new_authority.pubkey());
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.account(&account).owner, new_authority.pubkey());
+    assert_eq!(h.account(&account).delegate, COption::None);
+    assert_eq!(h.account(&account).delegated_amount.0, 0);
+}
+
+#[test]
+fn multisig_mint_authority_needs_m_signers() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+    let members = [h.funded_keypair(), Keypair::new(), Keypair::new()];
+    let member_keys: Vec<Pubkey> = members.iter().map(|member| member.pubkey()).collect();
+
+    let multisig = Keypair::new();
+    let ix = client::initialize_multisig(multisig.pubkey(), h.payer(), 2, &member_keys);
+    h.send(&[ix], &[&multisig]).unwrap();
+    let ix = client::set_mint_authority(mint, &h.authority(), Some(multisig.pubkey()));
+    h.send(&[ix], &[]).unwrap();
+
+    let one_signer = Authority::Multisig { multisig: multisig.pubkey(), signers: vec![member_keys[0]] };
+    let grants = [h.grant(&account, 5)];
+    let ix = client::mint_to(mint, account, &one_signer, ciphertext(5), 0, &grants);
+    assert!(h.send(&[ix], &[&members[0]]).is_err());
+
+    // The first member signs the Inco Lightning CPIs and pays for allowance accounts
+    let two_signers = Authority::Multisig { multisig: multisig.pubkey(), signers: vec![member_keys[0], member_keys[2]] };
+    let ix = client::mint_to(mint, account, &two_signers, ciphertext(5), 0, &grants);
+    h.send(&[ix], &[&members[0], &members[2]]).unwrap();
+    assert_eq!(h.balance(&account), 5);
+}
+
+#[test]
+fn auditor_is_granted_every_new_handle() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+    let auditor = Pubkey::new_unique();
+
+    let ix = client::set_auditor(mint, &h.authority(), Some(auditor));
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.mint(&mint).auditor, COption::Some(auditor));
+
+    // Without the auditor's allowance accounts the instruction fails
+    let ix = client::mint_to(mint, account, &h.authority(), ciphertext(7), 0, &[]);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    // amount, new balance and new supply all equal 7 here
+    let owner_grant = AllowanceGrant::new(7, h.payer());
+    let auditor_grant = AllowanceGrant::new(7, auditor);
+    let ix = client::mint_to(mint, account, &h.authority(), ciphertext(7), 0, &[owner_grant, auditor_grant]);
+    h.send(&[ix], &[]).unwrap();
+    assert!(h.svm.get_account(&client::allowance_address(Euint128(7), &auditor)).is_some());
+
+    let ix = client::set_auditor(mint, &h.authority(), None);
+    h.send(&[ix], &[]).unwrap();
+    h.mint_to(&mint, &account, 1);
+    assert_eq!(h.balance(&account), 8);
+}
+
+/// Shrink a mint back to the `len` bytes of an older IncoMint layout
+fn truncate_mint(h: &mut Harness, mint: &Pubkey, len: usize) {
+    let mut mint_account = h.svm.get_account(mint).unwrap();
+    mint_account.data.truncate(len);
+    h.svm.set_account(*mint, mint_account).unwrap();
+}
+
+#[test]
+fn migrate_mint_makes_room_for_an_auditor() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    // Mints created before the auditor field were 8 + 106 bytes
+    truncate_mint(&mut h, &mint, 8 + 106);
+    assert_eq!(h.mint(&mint).auditor, COption::None);
+
+    let ix = client::set_auditor(mint, &h.authority(), Some(Pubkey::new_unique()));
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let ix = client::migrate_mint(mint, h.payer());
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.svm.get_account(&mint).unwrap().data.len(), 8 + IncoMint::LEN);
+    let ix = client::migrate_mint(mint, h.payer());
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let auditor = Pubkey::new_unique();
+    let ix = client::set_auditor(mint, &h.authority(), Some(auditor));
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.mint(&mint).auditor, COption::Some(auditor));
+}
+
+#[test]
+fn max_supply_caps_minting() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+
+    let grants = [AllowanceGrant::new(150, h.payer())];
+    let ix = client::set_max_supply(mint, &h.authority(), AmountInput::Plaintext(150), &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.mint(&mint).max_supply.to_option().map(|max_supply| max_supply.0), Some(150));
+
+    h.mint_to(&mint, &account, 100);
+    h.mint_to(&mint, &account, 60);
+    assert_eq!(h.balance(&account), 100);
+    assert_eq!(h.supply(&mint), 100);
+
+    let ix = client::mint_to_checked(mint, account, &h.authority(), ciphertext(50), 0, 9, &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.supply(&mint), 150);
+
+    // The cap can only be set once
+    let max_supply = AmountInput::Encrypted { ciphertext: ciphertext(1_000), input_type: 0 };
+    let ix = client::set_max_supply(mint, &h.authority(), max_supply, &grants);
+    assert!(h.send(&[ix], &[]).is_err());
+}
+
+#[test]
+fn migrate_mint_grows_mints_created_before_max_supply() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    // Mints with an auditor but no max_supply field were 8 + 142 bytes
+    truncate_mint(&mut h, &mint, 8 + 142);
+    assert_eq!(h.mint(&mint).max_supply, COption::None);
+
+    let ix = client::migrate_mint(mint, h.payer());
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.svm.get_account(&mint).unwrap().data.len(), 8 + IncoMint::LEN);
+
+    let grants = [AllowanceGrant::new(150, h.payer())];
+    let ix = client::set_max_supply(mint, &h.authority(), AmountInput::Plaintext(150), &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.mint(&mint).max_supply.to_option().map(|max_supply| max_supply.0), Some(150));
+}
+
+#[test]
+fn native_mint_wraps_and_unwraps_lamports() {
+    let mut h = Harness::new();
+    let ix = client::initialize_native_mint(h.payer());
+    h.send(&[ix], &[]).unwrap();
+    let native_mint = client::native_mint_address();
+    let account = h.create_account(&native_mint, &h.payer());
+
+    let deposit = LAMPORTS_PER_SOL;
+    let ix = system_instruction::transfer(&h.payer(), &account, deposit);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::sync_native(account, h.payer(), &[h.grant(&account, deposit as u128)]);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&account), deposit as u128);
+    assert_eq!(h.supply(&native_mint), deposit as u128);
+
+    // Native accounts cannot burn
+    let ix = client::burn(account, native_mint, &h.authority(), ciphertext(1), 0, &[]);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let destination = Pubkey::new_unique();
+    let ix = client::unwrap_native(account, destination, &h.authority(), deposit as u128, &[h.grant(&account, 0)]);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&account), 0);
+    assert_eq!(h.supply(&native_mint), 0);
+    assert_eq!(h.lamports(&destination), deposit);
+}
+
+// ========== ESCROW INSTRUCTIONS ==========
+
+struct EscrowSetup {
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    maker_a: Pubkey,
+    maker_b: Pubkey,
+    taker: Keypair,
+    taker_a: Pubkey,
+    taker_b: Pubkey,
+}
+
+/// Maker holds 100 of mint A, taker holds `taker_balance` of mint B
+fn escrow_setup(h: &mut Harness, taker_balance: u128) -> EscrowSetup {
+    let taker = h.funded_keypair();
+    let mint_a = h.create_mint(9);
+    let mint_b = h.create_mint(9);
+    let maker_a = h.create_account(&mint_a, &h.payer());
+    let maker_b = h.create_account(&mint_b, &h.payer());
+    let taker_a = h.create_account(&mint_a, &taker.pubkey());
+    let taker_b = h.create_account(&mint_b, &taker.pubkey());
+    h.mint_to(&mint_a, &maker_a, 100);
+    h.mint_to(&mint_b, &taker_b, taker_balance);
+    EscrowSetup { mint_a, mint_b, maker_a, maker_b, taker, taker_a, taker_b }
+}
+
+fn make_escrow(h: &mut Harness, setup: &EscrowSetup, seed: u64, offered: u128, requested: u128, expiry: Option<i64>) -> Pubkey {
+    // The lock is all or nothing
+    let balance = h.balance(&setup.maker_a);
+    let locked = if balance >= offered { offered } else { 0 };
+    let grants = [AllowanceGrant::new(balance - locked, h.payer()), AllowanceGrant::new(locked, h.payer())];
+    let ix = client::make_escrow(
+        setup.maker_a,
+        setup.maker_b,
+        setup.mint_a,
+        setup.mint_b,
+        &h.authority(),
+        h.payer(),
+        seed,
+        ciphertext(offered),
+        ciphertext(requested),
+        0,
+        None,
+        expiry,
+        &grants,
+    );
+    h.send(&[ix], &[]).unwrap();
+    client::escrow_address(&h.payer(), seed)
+}
+
+fn escrow_state(h: &Harness, escrow: &Pubkey) -> IncoEscrow {
+    client::decode_escrow(&h.svm.get_account(escrow).unwrap().data).unwrap()
+}
+
+/// Taker source and receive balances, then the maker receive and vault balances
+fn fill_grants(h: &Harness, setup: &EscrowSetup, balances: [u128; 4]) -> [AllowanceGrant; 4] {
+    let taker = setup.taker.pubkey();
+    [
+        AllowanceGrant::new(balances[0], taker),
+        AllowanceGrant::new(balances[1], taker),
+        AllowanceGrant::new(balances[2], h.payer()),
+        AllowanceGrant::new(balances[3], h.payer()),
+    ]
+}
+
+#[test]
+fn escrow_fill_settles_both_legs() {
+    let mut h = Harness::new();
+    let setup = escrow_setup(&mut h, 80);
+    let escrow = make_escrow(&mut h, &setup, 1, 60, 50, None);
+    let state = escrow_state(&h, &escrow);
+    assert_eq!(h.balance(&setup.maker_a), 40);
+    assert_eq!(h.balance(&state.vault), 60);
+    assert_eq!(h.account(&state.vault).owner, escrow);
+
+    let taker = Authority::Single(setup.taker.pubkey());
+    let grants = fill_grants(&h, &setup, [30, 60, 50, 0]);
+    let ix = client::fill_escrow(escrow, &state, setup.taker_b, setup.taker_a, &taker, ciphertext(60), ciphertext(50), 0, &grants);
+    h.send(&[ix], &[&setup.taker]).unwrap();
+    assert_eq!(h.balance(&setup.taker_b), 30);
+    assert_eq!(h.balance(&setup.maker_b), 50);
+    assert_eq!(h.balance(&setup.taker_a), 60);
+    assert_eq!(h.balance(&state.vault), 0);
+
+    // The emptied vault cannot be filled again
+    let ix = client::fill_escrow(escrow, &state, setup.taker_b, setup.taker_a, &taker, ciphertext(60), ciphertext(50), 0, &grants);
+    h.send(&[ix], &[&setup.taker]).unwrap();
+    assert_eq!(h.balance(&setup.taker_b), 30);
+    assert_eq!(h.balance(&setup.taker_a), 60);
+
+    let ix = client::cancel_escrow(escrow, &state, &h.authority(), &[h.grant(&setup.maker_a, 40)]);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.lamports(&escrow), 0);
+    assert_eq!(h.lamports(&state.vault), 0);
+    assert_eq!(h.balance(&setup.maker_a), 40);
+}
+
+#[test]
+fn escrow_fill_on_other_terms_moves_nothing() {
+    let mut h = Harness::new();
+    let setup = escrow_setup(&mut h, 80);
+    let taker = Authority::Single(setup.taker.pubkey());
+    // Open to any taker, so only the terms the taker expects guard the fill
+    let escrow = make_escrow(&mut h, &setup, 1, 60, 50, None);
+    let state = escrow_state(&h, &escrow);
+
+    let grants = fill_grants(&h, &setup, [80, 0, 0, 60]);
+    for (offered, requested) in [(60, 40), (50, 50)] {
+        let ix = client::fill_escrow(
+            escrow, &state, setup.taker_b, setup.taker_a, &taker, ciphertext(offered), ciphertext(requested), 0, &grants,
+        );
+        h.send(&[ix], &[&setup.taker]).unwrap();
+        assert_eq!(h.balance(&setup.taker_b), 80);
+        assert_eq!(h.balance(&setup.maker_b), 0);
+        assert_eq!(h.balance(&state.vault), 60);
+    }
+
+    let grants = fill_grants(&h, &setup, [30, 60, 50, 0]);
+    let ix = client::fill_escrow(escrow, &state, setup.taker_b, setup.taker_a, &taker, ciphertext(60), ciphertext(50), 0, &grants);
+    h.send(&[ix], &[&setup.taker]).unwrap();
+    assert_eq!(h.balance(&setup.maker_b), 50);
+    assert_eq!(h.balance(&setup.taker_a), 60);
+}
+
+#[test]
+fn escrow_moves_nothing_when_either_side_is_short() {
+    let mut h = Harness::new();
+    let setup = escrow_setup(&mut h, 40);
+    let taker = Authority::Single(setup.taker.pubkey());
+
+    // The taker cannot pay the requested 50
+    let escrow = make_escrow(&mut h, &setup, 1, 60, 50, None);
+    let state = escrow_state(&h, &escrow);
+    let grants = fill_grants(&h, &setup, [40, 0, 0, 60]);
+    let ix = client::fill_escrow(escrow, &state, setup.taker_b, setup.taker_a, &taker, ciphertext(60), ciphertext(50), 0, &grants);
+    h.send(&[ix], &[&setup.taker]).unwrap();
+    assert_eq!(h.balance(&setup.taker_b), 40);
+    assert_eq!(h.balance(&setup.taker_a), 0);
+    assert_eq!(h.balance(&state.vault), 60);
+
+    // The maker cannot lock 60 more, so the vault is empty and nothing is owed
+    let underfunded = make_escrow(&mut h, &setup, 2, 60, 10, None);
+    let underfunded_state = escrow_state(&h, &underfunded);
+    assert_eq!(h.balance(&underfunded_state.vault), 0);
+    assert_eq!(h.balance(&setup.maker_a), 40);
+    let grants = fill_grants(&h, &setup, [40, 0, 0, 0]);
+    let ix = client::fill_escrow(underfunded, &underfunded_state, setup.taker_b, setup.taker_a, &taker, ciphertext(60), ciphertext(10), 0, &grants);
+    h.send(&[ix], &[&setup.taker]).unwrap();
+    assert_eq!(h.balance(&setup.taker_b), 40);
+    assert_eq!(h.balance(&setup.maker_b), 0);
+
+    // Only the maker can cancel before expiry
+    let grants = [h.grant(&setup.maker_a, 100)];
+    let ix = client::cancel_escrow(escrow, &state, &taker, &grants);
+    assert!(h.send(&[ix], &[&setup.taker]).is_err());
+    let ix = client::cancel_escrow(escrow, &state, &h.authority(), &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&setup.maker_a), 100);
+}
+
+#[test]
+fn expired_escrow_cannot_be_filled_and_anyone_can_refund_it() {
+    let mut h = Harness::new();
+    let setup = escrow_setup(&mut h, 80);
+    let taker = Authority::Single(setup.taker.pubkey());
+    let mut clock = h.svm.get_sysvar::<Clock>();
+    let escrow = make_escrow(&mut h, &setup, 1, 60, 50, Some(clock.unix_timestamp + 100));
+    let state = escrow_state(&h, &escrow);
+
+    clock.unix_timestamp += 100;
+    h.svm.set_sysvar::<Clock>(&clock);
+
+    let grants = fill_grants(&h, &setup, [30, 60, 50, 0]);
+    let ix = client::fill_escrow(escrow, &state, setup.taker_b, setup.taker_a, &taker, ciphertext(60), ciphertext(50), 0, &grants);
+    assert!(h.send(&[ix], &[&setup.taker]).is_err());
+
+    let ix = client::cancel_escrow(escrow, &state, &taker, &[h.grant(&setup.maker_a, 100)]);
+    h.send(&[ix], &[&setup.taker]).unwrap();
+    assert_eq!(h.balance(&setup.maker_a), 100);
+    assert_eq!(h.lamports(&escrow), 0);
+}
+
+// ========== VESTING INSTRUCTIONS ==========
+
+#[test]
+fn vesting_unlocks_per_period_after_cliff() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let source = h.create_account(&mint, &h.payer());
+    let beneficiary = h.funded_keypair();
+    let destination = h.create_account(&mint, &beneficiary.pubkey());
+    h.mint_to(&mint, &source, 1_500);
+
+    let mut clock = h.svm.get_sysvar::<Clock>();
+    let start = clock.unix_timestamp;
+    let schedule = VestingSchedule { start, cliff: start + 100, end: start + 400, period: 100 };
+
+    let grants = [
+        AllowanceGrant::new(500, h.payer()),
+        AllowanceGrant::new(1_000, h.payer()),
+        AllowanceGrant::new(1_000, beneficiary.pubkey()),
+    ];
+    let invalid = VestingSchedule { period: 150, ..schedule };
+    let ix = client::create_vesting(source, mint, beneficiary.pubkey(), &h.authority(), h.payer(), 1, ciphertext(1_000), 0, invalid, &grants);
+    assert!(h.send(&[ix], &[]).is_err());
+    // A duration that does not fit in an i64 is rejected rather than overflowing
+    let unbounded = VestingSchedule { start: i64::MIN, cliff: i64::MIN, end: i64::MAX, period: 1 };
+    assert!(unbounded.validate().is_err());
+
+    let ix = client::create_vesting(source, mint, beneficiary.pubkey(), &h.authority(), h.payer(), 1, ciphertext(1_000), 0, schedule, &grants);
+    h.send(&[ix], &[]).unwrap();
+    let vesting = client::vesting_address(&h.payer(), 1);
+    let state = client::decode_vesting(&h.svm.get_account(&vesting).unwrap().data).unwrap();
+    assert_eq!(h.balance(&source), 500);
+    assert_eq!(h.balance(&state.vault), 1_000);
+
+    // The new destination balance and claimed total both equal the unlocked amount here
+    let claim_grants = |unlocked| [AllowanceGrant::new(unlocked, beneficiary.pubkey()); 2];
+    let as_beneficiary = Authority::Single(beneficiary.pubkey());
+    let ix = client::claim(vesting, &state, destination, &as_beneficiary, &claim_grants(0));
+    assert!(h.send(&[ix], &[&beneficiary]).is_err());
+
+    let mut claim_at = |h: &mut Harness, offset: i64, unlocked: u128| {
+        clock.unix_timestamp = start + offset;
+        h.svm.set_sysvar::<Clock>(&clock);
+        let ix = client::claim(vesting, &state, destination, &as_beneficiary, &claim_grants(unlocked));
+        h.send(&[ix], &[&beneficiary]).unwrap();
+    };
+
+    claim_at(&mut h, 100, 250);
+    assert_eq!(h.balance(&destination), 250);
+
+    claim_at(&mut h, 299, 500);
+    assert_eq!(h.balance(&destination), 500);
+    assert_eq!(h.balance(&state.vault), 500);
+
+    let rent = h.lamports(&vesting) + h.lamports(&state.vault);
+    let grantor_lamports = h.lamports(&h.payer());
+    claim_at(&mut h, 400, 1_000);
+    assert_eq!(h.balance(&destination), 1_000);
+    assert_eq!(h.lamports(&vesting), 0);
+    assert_eq!(h.lamports(&state.vault), 0);
+    // The payer also paid the transaction fee
+    assert!(h.lamports(&h.payer()) > grantor_lamports + rent - LAMPORTS_PER_SOL / 1_000);
+}
+
+// ========== TOKEN 2022 INSTRUCTIONS ==========
+
+#[test]
+fn checked_instructions_validate_decimals() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(6);
+    let source = Keypair::new();
+    let ix = client::initialize_account3(source.pubkey(), mint, h.payer());
+    h.send(&[ix], &[&source]).unwrap();
+    let source = source.pubkey();
+    let destination = h.create_account(&mint, &h.payer());
+    let delegate = Pubkey::new_unique();
+
+    let grants = [h.grant(&source, 100)];
+    let ix = client::mint_to_checked(mint, source, &h.authority(), ciphertext(100), 0, 9, &grants);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::mint_to_checked(mint, source, &h.authority(), ciphertext(100), 0, 6, &grants);
+    h.send(&[ix], &[]).unwrap();
+
+    let grants = [h.grant(&source, 70), h.grant(&destination, 30)];
+    let ix = client::transfer_checked(source, mint, destination, &h.authority(), ciphertext(30), 0, 6, &grants);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::burn_checked(source, mint, &h.authority(), ciphertext(20), 0, 6, &[h.grant(&source, 50)]);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&source), 50);
+    assert_eq!(h.balance(&destination), 30);
+    assert_eq!(h.supply(&mint), 80);
+
+    let grants = [AllowanceGrant::new(15, delegate)];
+    let ix = client::approve_checked(source, mint, delegate, &h.authority(), ciphertext(15), 0, 6, &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.account(&source).delegated_amount.0, 15);
+    let ix = client::revoke_2022(source, &h.authority());
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.account(&source).delegated_amount.0, 0);
+
+    let grants = [h.grant(&source, 0), h.grant(&destination, 80)];
+    let ix = client::transfer_checked(source, mint, destination, &h.authority(), ciphertext(50), 0, 6, &grants);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::close_account_2022(source, h.payer(), &h.authority(), 0);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.lamports(&source), 0);
+}
+
+#[test]
+fn mint_extensions_follow_the_base_layout() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+    let metadata = Pubkey::new_unique();
+    assert_eq!(h.svm.get_account(&mint).unwrap().data.len(), mint_space(&[]));
+
+    // Not initialized before reallocating
+    let ix = client::initialize_metadata_pointer(mint, &h.authority(), Some(h.payer()), Some(metadata));
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::MetadataPointer]);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.svm.get_account(&mint).unwrap().data.len(), mint_space(&[ExtensionType::MetadataPointer]));
+
+    let ix = client::initialize_metadata_pointer(mint, &h.authority(), Some(h.payer()), Some(metadata));
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_metadata_pointer(mint, &h.authority(), Some(h.payer()), None);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let ix = client::update_metadata_pointer(mint, &h.authority(), None);
+    h.send(&[ix], &[]).unwrap();
+    let data = h.svm.get_account(&mint).unwrap().data;
+    let pointer = client::decode_mint_extension::<MetadataPointer>(&data).unwrap().unwrap();
+    assert_eq!(pointer.authority, COption::Some(h.payer()));
+    assert_eq!(pointer.metadata_address, COption::None);
+
+    // The base mint is unaffected, and mint extensions are rejected on accounts
+    h.mint_to(&mint, &account, 5);
+    assert_eq!(h.supply(&mint), 5);
+    let ix = client::reallocate(account, &h.authority(), h.payer(), vec![ExtensionType::MetadataPointer]);
+    assert!(h.send(&[ix], &[]).is_err());
+}
+
+/// Mint charging 1% on transfer_checked, capped at 5, with the payer as both fee authorities
+fn create_fee_mint(h: &mut Harness) -> Pubkey {
+    let payer = h.payer();
+    h.create_mint_with_extensions(9, vec![ExtensionType::TransferFeeConfig], |mint| {
+        vec![client::initialize_transfer_fee_config(
+            mint,
+            &Authority::Single(payer),
+            Some(payer),
+            Some(payer),
+            100,
+            AmountInput::Plaintext(5),
+            &[AllowanceGrant::new(5, payer)],
+        )]
+    })
+}
+
+fn withheld(h: &Harness, account: &Pubkey) -> u128 {
+    let data = h.svm.get_account(account).unwrap().data;
+    client::decode_account_extension::<TransferFeeAmount>(&data).unwrap().unwrap().withheld_amount.0
+}
+
+#[test]
+fn transfer_fee_is_withheld_harvested_and_withdrawn() {
+    let mut h = Harness::new();
+    let mint = create_fee_mint(&mut h);
+    let source = h.create_account(&mint, &h.payer());
+    let destination = h.create_account(&mint, &h.payer());
+    let treasury = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 1_000);
+    assert_eq!(withheld(&h, &destination), 0);
+
+    // Fee mints only move through transfer_checked
+    assert!(h.transfer(&mint, &source, &destination, 10).is_err());
+
+    // 1% of 300 is 3; 1% of 700 rounds to 7 and is capped at 5
+    let grants = [h.grant(&source, 700), h.grant(&destination, 297)];
+    let ix = client::transfer_checked(source, mint, destination, &h.authority(), ciphertext(300), 0, 9, &grants);
+    h.send(&[ix], &[]).unwrap();
+    let grants = [h.grant(&source, 0), h.grant(&destination, 992)];
+    let ix = client::transfer_checked(source, mint, destination, &h.authority(), ciphertext(700), 0, 9, &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&source), 0);
+    assert_eq!(h.balance(&destination), 992);
+    let data = h.svm.get_account(&destination).unwrap().data;
+    let withheld = client::decode_account_extension::<TransferFeeAmount>(&data).unwrap().unwrap();
+    assert_eq!(withheld.withheld_amount.0, 8);
+
+    let ix = client::harvest_withheld_to_mint(mint, h.payer(), &[destination]);
+    h.send(&[ix], &[]).unwrap();
+    let data = h.svm.get_account(&destination).unwrap().data;
+    let withheld = client::decode_account_extension::<TransferFeeAmount>(&data).unwrap().unwrap();
+    assert_eq!(withheld.withheld_amount.0, 0);
+    let data = h.svm.get_account(&mint).unwrap().data;
+    let config = client::decode_mint_extension::<TransferFeeConfig>(&data).unwrap().unwrap();
+    assert_eq!(config.withheld_amount.0, 8);
+
+    let grants = [h.grant(&treasury, 8)];
+    let ix = client::withdraw_withheld_tokens(mint, treasury, &h.authority(), &[destination], &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&treasury), 8);
+    let data = h.svm.get_account(&mint).unwrap().data;
+    let config = client::decode_mint_extension::<TransferFeeConfig>(&data).unwrap().unwrap();
+    assert_eq!(config.withheld_amount.0, 0);
+
+    let ix = client::set_transfer_fee(mint, &h.authority(), 10_001, AmountInput::Plaintext(5), &[AllowanceGrant::new(5, h.payer())]);
+    assert!(h.send(&[ix], &[]).is_err());
+}
+
+#[test]
+fn transfer_fee_is_set_up_with_the_mint_only() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let account = h.create_account(&mint, &h.payer());
+
+    // Once the mint exists its accounts may lack a TransferFeeAmount
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::TransferFeeConfig]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_transfer_fee_config(
+        mint,
+        &h.authority(),
+        Some(h.payer()),
+        Some(h.payer()),
+        100,
+        AmountInput::Plaintext(5),
+        &[AllowanceGrant::new(5, h.payer())],
+    );
+    assert!(h.send(&[ix], &[]).is_err());
+    assert!(client::decode_account_extension::<TransferFeeAmount>(&h.svm.get_account(&account).unwrap().data)
+        .unwrap()
+        .is_none());
+
+    // Accounts of a fee mint get one however they are created
+    let mint = create_fee_mint(&mut h);
+    let wallet = Pubkey::new_unique();
+    let ix = client::create(h.payer(), wallet, mint);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(withheld(&h, &client::get_associated_token_address(&wallet, &mint)), 0);
+    let account = Keypair::new();
+    let ix = client::initialize_account3(account.pubkey(), mint, h.payer());
+    h.send(&[ix], &[&account]).unwrap();
+    assert_eq!(withheld(&h, &account.pubkey()), 0);
+}
+
+#[test]
+fn new_transfer_fee_applies_two_epochs_later() {
+    let mut h = Harness::new();
+    let mint = create_fee_mint(&mut h);
+    let source = h.create_account(&mint, &h.payer());
+    let destination = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 1_000);
+
+    // 10%, capped at 50
+    let ix = client::set_transfer_fee(mint, &h.authority(), 1_000, AmountInput::Plaintext(50), &[AllowanceGrant::new(50, h.payer())]);
+    h.send(&[ix], &[]).unwrap();
+
+    // The old fee still applies in the epoch of the change and the next one
+    let mut balances = (1_000, 0);
+    for (elapsed_epochs, fee) in [(0, 1), (1, 1), (1, 10)] {
+        let mut clock = h.svm.get_sysvar::<Clock>();
+        clock.epoch += elapsed_epochs;
+        h.svm.set_sysvar::<Clock>(&clock);
+
+        balances = (balances.0 - 100, balances.1 + 100 - fee);
+        let grants = [h.grant(&source, balances.0), h.grant(&destination, balances.1)];
+        let ix = client::transfer_checked(source, mint, destination, &h.authority(), ciphertext(100), 0, 9, &grants);
+        h.send(&[ix], &[]).unwrap();
+        assert_eq!(h.balance(&destination), balances.1);
+    }
+    assert_eq!(withheld(&h, &destination), 12);
+}
+
+#[test]
+fn closing_an_account_requires_its_withheld_fees_harvested() {
+    let mut h = Harness::new();
+    let mint = create_fee_mint(&mut h);
+    let source = h.create_account(&mint, &h.payer());
+    let account = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 100);
+
+    let grants = [h.grant(&source, 0), h.grant(&account, 99)];
+    let ix = client::transfer_checked(source, mint, account, &h.authority(), ciphertext(100), 0, 9, &grants);
+    h.send(&[ix], &[]).unwrap();
+    let grants = [h.grant(&account, 0), h.grant(&source, 98)];
+    let ix = client::transfer_checked(account, mint, source, &h.authority(), ciphertext(99), 0, 9, &grants);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&account), 0);
+    assert_eq!(withheld(&h, &account), 1);
+
+    let ix = client::close_account(account, h.payer(), &h.authority(), 0);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::close_account_2022(account, h.payer(), &h.authority(), 0);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let ix = client::harvest_withheld_to_mint(mint, h.payer(), &[account]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::close_account_2022(account, h.payer(), &h.authority(), 0);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.lamports(&account), 0);
+}
+
+#[test]
+fn transfer_hook_can_reject_transfers() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let source = h.create_account(&mint, &h.payer());
+    let destination = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 1_000);
+
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::TransferHook]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_transfer_hook(mint, &h.authority(), Some(h.payer()), Some(MOCK_TRANSFER_HOOK_ID));
+    h.send(&[ix], &[]).unwrap();
+    let hook_accounts = client::transfer_hook_account_metas(&mint, &MOCK_TRANSFER_HOOK_ID, &[]);
+
+    // The hook accounts are required
+    assert!(h.transfer(&mint, &source, &destination, 50).is_err());
+
+    let grants = [h.grant(&source, 950), h.grant(&destination, 50)];
+    let mut ix = client::transfer(source, destination, mint, &h.authority(), ciphertext(50), 0, &grants);
+    ix.accounts.extend(hook_accounts.clone());
+    h.send(&[ix], &[]).unwrap();
+    let grants = [h.grant(&source, 890), h.grant(&destination, 110)];
+    let mut ix = client::transfer_checked(source, mint, destination, &h.authority(), ciphertext(60), 0, 9, &grants);
+    ix.accounts.extend(hook_accounts.clone());
+    h.send(&[ix], &[]).unwrap();
+
+    // A failing hook fails the transfer
+    let grants = [h.grant(&source, 740), h.grant(&destination, 260)];
+    let mut ix = client::transfer(source, destination, mint, &h.authority(), ciphertext(150), 0, &grants);
+    ix.accounts.extend(hook_accounts.clone());
+    assert!(h.send(&[ix], &[]).is_err());
+    assert_eq!(h.balance(&source), 890);
+    assert_eq!(h.balance(&destination), 110);
+
+    // Instructions that cannot invoke the hook are rejected
+    let ix = client::transfer_batch(source, mint, &h.authority(), vec![(destination, ciphertext(1))], 0, &[]);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let ix = client::update_transfer_hook(mint, &h.authority(), None);
+    h.send(&[ix], &[]).unwrap();
+    h.transfer(&mint, &source, &destination, 500).unwrap();
+    assert_eq!(h.balance(&destination), 610);
+}
+
+#[test]
+fn transfer_hook_gets_exactly_the_accounts_it_lists() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let source = h.create_account(&mint, &h.payer());
+    let destination = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 1_000);
+
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::TransferHook]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_transfer_hook(mint, &h.authority(), Some(h.payer()), Some(MOCK_TRANSFER_HOOK_ID));
+    h.send(&[ix], &[]).unwrap();
+
+    // A fixed allowlist and a per-destination PDA of the hook: [SEED_LITERAL, 5, "entry", SEED_ACCOUNT_KEY, 2]
+    let allowlist = Pubkey::new_unique();
+    let mut seeds = [0u8; 32];
+    seeds[..9].copy_from_slice(&[SEED_LITERAL, 5, b'e', b'n', b't', b'r', b'y', SEED_ACCOUNT_KEY, 2]);
+    let entry = Pubkey::find_program_address(&[b"entry", destination.as_ref()], &MOCK_TRANSFER_HOOK_ID).0;
+    let extra_account_metas = [
+        ExtraAccountMeta {
+            discriminator: EXTRA_ACCOUNT_FIXED,
+            address_config: allowlist.to_bytes(),
+            is_signer: false,
+            is_writable: false,
+        },
+        ExtraAccountMeta {
+            discriminator: EXTRA_ACCOUNT_HOOK_PDA,
+            address_config: seeds,
+            is_signer: false,
+            is_writable: true,
+        },
+    ];
+    let metas_account = Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: client::extra_account_metas_data(&extra_account_metas),
+        owner: MOCK_TRANSFER_HOOK_ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    h.svm.set_account(extra_account_metas_address(&mint, &MOCK_TRANSFER_HOOK_ID), metas_account).unwrap();
+
+    let grants = [h.grant(&source, 950), h.grant(&destination, 50)];
+    let listed = AccountMeta::new_readonly(allowlist, false);
+    let unlisted = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    // Missing, without the listed write access, not the listed address, or one too many
+    let rejected = [
+        vec![],
+        vec![listed.clone(), AccountMeta::new_readonly(entry, false)],
+        vec![unlisted.clone(), AccountMeta::new(entry, false)],
+        vec![listed.clone(), AccountMeta::new(entry, false), unlisted],
+    ];
+    for extra_accounts in rejected {
+        let mut ix = client::transfer(source, destination, mint, &h.authority(), ciphertext(50), 0, &grants);
+        ix.accounts.extend(client::transfer_hook_account_metas(&mint, &MOCK_TRANSFER_HOOK_ID, &extra_accounts));
+        assert!(h.send(&[ix], &[]).is_err());
+    }
+
+    let extra_accounts = [listed, AccountMeta::new(entry, false)];
+    let mut ix = client::transfer(source, destination, mint, &h.authority(), ciphertext(50), 0, &grants);
+    ix.accounts.extend(client::transfer_hook_account_metas(&mint, &MOCK_TRANSFER_HOOK_ID, &extra_accounts));
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&destination), 50);
+}
+
+#[test]
+fn transfer_hook_and_required_memo_combine() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let source = h.create_account(&mint, &h.payer());
+    let deposit = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 100);
+
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::TransferHook]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_transfer_hook(mint, &h.authority(), Some(h.payer()), Some(MOCK_TRANSFER_HOOK_ID));
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::reallocate(deposit, &h.authority(), h.payer(), vec![ExtensionType::MemoTransfer]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::enable_required_transfer_memos(deposit, &h.authority());
+    h.send(&[ix], &[]).unwrap();
+    let hook_accounts = client::transfer_hook_account_metas(&mint, &MOCK_TRANSFER_HOOK_ID, &[]);
+
+    // The hook accounts run to the end, so the sysvar cannot follow them
+    let memo = client::build_memo(h.payer(), ciphertext(7), 0, &[]);
+    let grants = [h.grant(&source, 90), h.grant(&deposit, 10)];
+    let mut ix = client::transfer(source, deposit, mint, &h.authority(), ciphertext(10), 0, &grants);
+    ix.accounts.extend(hook_accounts.clone());
+    ix.accounts.push(client::transfer_memo_account_meta());
+    assert!(h.send(&[memo.clone(), ix], &[]).is_err());
+
+    let mut ix = client::transfer(source, deposit, mint, &h.authority(), ciphertext(10), 0, &grants);
+    ix.accounts.push(client::transfer_memo_account_meta());
+    ix.accounts.extend(hook_accounts.clone());
+    h.send(&[memo, ix], &[]).unwrap();
+
+    let memo = client::build_memo(h.payer(), ciphertext(8), 0, &[]);
+    let grants = [h.grant(&source, 70), h.grant(&deposit, 30)];
+    let mut ix = client::transfer_checked(source, mint, deposit, &h.authority(), ciphertext(20), 0, 9, &grants);
+    ix.accounts.push(client::transfer_memo_account_meta());
+    ix.accounts.extend(hook_accounts);
+    h.send(&[memo, ix], &[]).unwrap();
+    assert_eq!(h.balance(&deposit), 30);
+}
+
+#[test]
+fn new_accounts_start_in_the_default_state() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let wallet = Pubkey::new_unique();
+    let address = client::get_associated_token_address(&wallet, &mint);
+
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::DefaultAccountState]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_default_account_state(mint, &h.authority(), AccountState::Uninitialized);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::initialize_default_account_state(mint, &h.authority(), AccountState::Frozen);
+    h.send(&[ix], &[]).unwrap();
+
+    let account = h.create_account(&mint, &h.payer());
+    assert!(h.account(&account).state == AccountState::Frozen);
+    let ix = client::create(h.payer(), wallet, mint);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::create_idempotent(h.payer(), wallet, mint);
+    h.send(&[ix], &[]).unwrap();
+    assert!(h.account(&address).state == AccountState::Frozen);
+
+    let ix = client::update_default_account_state(mint, &h.authority(), AccountState::Initialized);
+    h.send(&[ix], &[]).unwrap();
+    let source = h.create_account(&mint, &h.payer());
+    assert!(h.account(&source).state == AccountState::Initialized);
+    h.mint_to(&mint, &source, 10);
+
+    // Frozen accounts cannot receive until the freeze authority thaws them
+    assert!(h.transfer(&mint, &source, &account, 10).is_err());
+    let ix = client::thaw_account(account, mint, &h.authority());
+    h.send(&[ix], &[]).unwrap();
+    h.transfer(&mint, &source, &account, 10).unwrap();
+    assert_eq!(h.balance(&account), 10);
+}
+
+#[test]
+fn non_transferable_tokens_can_only_be_minted_and_burned() {
+    let mut h = Harness::new();
+    let authority = h.authority();
+    let mint = h.create_mint_with_extensions(9, vec![ExtensionType::NonTransferable], |mint| {
+        vec![client::initialize_non_transferable_mint(mint, &authority)]
+    });
+    assert_eq!(h.svm.get_account(&mint).unwrap().data.len(), mint_space(&[ExtensionType::NonTransferable]));
+    let account = h.create_account(&mint, &h.payer());
+    let other = h.create_account(&mint, &h.payer());
+
+    h.mint_to(&mint, &account, 10);
+    assert!(h.transfer(&mint, &account, &other, 1).is_err());
+    let ix = client::transfer_checked(account, mint, other, &h.authority(), ciphertext(1), 0, 9, &[]);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::approve(account, mint, Pubkey::new_unique(), &h.authority(), ciphertext(1), 0, &[]);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::approve_checked(account, mint, Pubkey::new_unique(), &h.authority(), ciphertext(1), 0, 9, &[]);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    // Nor can the account itself change hands
+    let ix = client::set_account_owner(account, &h.authority(), Pubkey::new_unique());
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::set_authority(account, &h.authority(), AuthorityType::AccountOwner, Some(Pubkey::new_unique()));
+    assert!(h.send(&[ix], &[]).is_err());
+    assert_eq!(h.account(&account).owner, h.payer());
+
+    let ix = client::burn(account, mint, &h.authority(), ciphertext(4), 0, &[h.grant(&account, 6)]);
+    h.send(&[ix], &[]).unwrap();
+    assert_eq!(h.balance(&account), 6);
+    assert_eq!(h.supply(&mint), 6);
+
+    // An existing mint cannot be made non-transferable under its accounts
+    let mint = h.create_mint(9);
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::NonTransferable]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_non_transferable_mint(mint, &h.authority());
+    assert!(h.send(&[ix], &[]).is_err());
+}
+
+#[test]
+fn permanent_delegate_claws_back_from_any_account() {
+    let mut h = Harness::new();
+    let holder = Pubkey::new_unique();
+    let clawback = h.funded_keypair();
+    let (authority, delegate) = (h.authority(), clawback.pubkey());
+    let mint = h.create_mint_with_extensions(9, vec![ExtensionType::PermanentDelegate], |mint| {
+        vec![client::initialize_permanent_delegate(mint, &authority, delegate)]
+    });
+    let account = h.create_account(&mint, &holder);
+    let treasury = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &account, 100);
+
+    // Clamped to the balance like any other transfer, without an approval
+    let as_delegate = Authority::Single(clawback.pubkey());
+    let grants = [h.grant(&account, 100), h.grant(&treasury, 0)];
+    let ix = client::transfer(account, treasury, mint, &as_delegate, ciphertext(150), 0, &grants);
+    h.send(&[ix], &[&clawback]).unwrap();
+    assert_eq!(h.balance(&account), 100);
+    let grants = [h.grant(&account, 40), h.grant(&treasury, 60)];
+    let ix = client::transfer_checked(account, mint, treasury, &as_delegate, ciphertext(60), 0, 9, &grants);
+    h.send(&[ix], &[&clawback]).unwrap();
+    let grants = [h.grant(&account, 30), h.grant(&treasury, 70)];
+    let ix = client::transfer_batch(account, mint, &as_delegate, vec![(treasury, ciphertext(10))], 0, &grants);
+    h.send(&[ix], &[&clawback]).unwrap();
+    let ix = client::burn(account, mint, &as_delegate, ciphertext(30), 0, &[h.grant(&account, 0)]);
+    h.send(&[ix], &[&clawback]).unwrap();
+    assert_eq!(h.balance(&account), 0);
+    assert_eq!(h.balance(&treasury), 70);
+    assert_eq!(h.supply(&mint), 70);
+
+    // Giving up the role ends it
+    let ix = client::update_permanent_delegate(mint, &as_delegate, None);
+    h.send(&[ix], &[&clawback]).unwrap();
+    let ix = client::burn(treasury, mint, &as_delegate, ciphertext(1), 0, &[h.grant(&treasury, 69)]);
+    assert!(h.send(&[ix], &[&clawback]).is_err());
+
+    // Holders of an existing mint cannot be put under a permanent delegate
+    let mint = h.create_mint(9);
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::PermanentDelegate]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_permanent_delegate(mint, &h.authority(), clawback.pubkey());
+    assert!(h.send(&[ix], &[]).is_err());
+}
+
+#[test]
+fn interest_accrues_on_the_displayed_amount_only() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(6);
+    let account = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &account, 1_000_000);
+
+    let ix = client::reallocate_mint(mint, &h.authority(), h.payer(), vec![ExtensionType::InterestBearingConfig]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_interest_bearing_mint(mint, &h.authority(), Some(h.payer()), 1_000);
+    h.send(&[ix], &[]).unwrap();
+
+    let ui_amount = |h: &mut Harness| {
+        let ix = client::amount_to_ui_amount(account, mint, h.payer(), &[AllowanceGrant::new(1_105_170, h.payer())]);
+        let meta = h.send(&[ix], &[]).unwrap();
+        u128::from_le_bytes(meta.return_data.data.try_into().unwrap())
+    };
+    let advance_one_year = |h: &mut Harness| {
+        let mut clock = h.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += 31_556_736;
+        h.svm.set_sysvar::<Clock>(&clock);
+    };
+
+    // 10% a year compounds continuously to e^0.1
+    advance_one_year(&mut h);
+    assert_eq!(ui_amount(&mut h), 1_105_170);
+    assert_eq!(h.balance(&account), 1_000_000);
+
+    // Accrued interest is kept when the rate changes
+    let ix = client::update_interest_rate(mint, &h.authority(), 0);
+    h.send(&[ix], &[]).unwrap();
+    advance_one_year(&mut h);
+    assert_eq!(ui_amount(&mut h), 1_105_170);
+
+    // From 2^16 on, the factor could overflow a scaled u64 balance, so it is refused
+    let config = InterestBearingConfig {
+        rate_authority: COption::None,
+        initialization_timestamp: 0,
+        pre_update_average_rate: i16::MAX,
+        last_update_timestamp: 0,
+        current_rate: i16::MAX,
+    };
+    assert!(config.accrued_factor(31_556_736).is_some());
+    assert!(config.accrued_factor(4 * 31_556_736).is_none());
+}
+
+#[test]
+fn close_mint_requires_the_close_authority_and_zero_supply() {
+    let mut h = Harness::new();
+    let closer = Keypair::new();
+    let destination = Pubkey::new_unique();
+
+    // Mints without the extension cannot be closed, nor gain it once initialized
+    let plain = h.create_mint(9);
+    let ix = client::close_mint(plain, destination, &h.authority(), 0);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::reallocate_mint(plain, &h.authority(), h.payer(), vec![ExtensionType::MintCloseAuthority]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::initialize_mint_close_authority(plain, &h.authority(), Some(h.payer()));
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let (authority, payer) = (h.authority(), h.payer());
+    let mint = h.create_mint_with_extensions(9, vec![ExtensionType::MintCloseAuthority], |mint| {
+        vec![client::initialize_mint_close_authority(mint, &authority, Some(payer))]
+    });
+    let account = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &account, 10);
+
+    let ix = client::update_mint_close_authority(mint, &h.authority(), Some(closer.pubkey()));
+    h.send(&[ix], &[]).unwrap();
+
+    // A false attestation, a non-zero supply and the wrong authority are rejected
+    let as_closer = Authority::Single(closer.pubkey());
+    let ix = client::close_mint(mint, destination, &as_closer, 0);
+    assert!(h.send(&[ix], &[&closer]).is_err());
+    let ix = client::close_mint(mint, destination, &as_closer, 10);
+    assert!(h.send(&[ix], &[&closer]).is_err());
+
+    let ix = client::burn(account, mint, &h.authority(), ciphertext(10), 0, &[h.grant(&account, 0)]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::close_mint(mint, destination, &h.authority(), 0);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    let rent = h.lamports(&mint);
+    let ix = client::close_mint(mint, destination, &as_closer, 0);
+    h.send(&[ix], &[&closer]).unwrap();
+    assert_eq!(h.lamports(&mint), 0);
+    assert_eq!(h.lamports(&destination), rent);
+}
+
+#[test]
+fn required_memos_gate_incoming_transfers() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let source = h.create_account(&mint, &h.payer());
+    let deposit = h.create_account(&mint, &h.payer());
+    h.mint_to(&mint, &source, 100);
+
+    let ix = client::reallocate(deposit, &h.authority(), h.payer(), vec![ExtensionType::MemoTransfer]);
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::enable_required_transfer_memos(deposit, &h.authority());
+    h.send(&[ix], &[]).unwrap();
+
+    // Without a memo, or with the memo in the wrong place, the transfer is rejected
+    assert!(h.transfer(&mint, &source, &deposit, 10).is_err());
+    let memo = client::build_memo(h.payer(), ciphertext(7), 0, &[]);
+    let grants = [h.grant(&source, 90), h.grant(&deposit, 10)];
+    let mut transfer = client::transfer(source, deposit, mint, &h.authority(), ciphertext(10), 0, &grants);
+    transfer.accounts.push(client::transfer_memo_account_meta());
+    assert!(h.send(&[transfer.clone(), memo.clone()], &[]).is_err());
+    let ix = client::transfer_batch(source, mint, &h.authority(), vec![(deposit, ciphertext(10))], 0, &[]);
+    assert!(h.send(&[memo.clone(), ix], &[]).is_err());
+
+    h.send(&[memo, transfer], &[]).unwrap();
+    let memo_grants = [AllowanceGrant::new(8, h.payer())];
+    let memo = client::build_memo_with_recipients(h.payer(), ciphertext(8), 0, &[], &memo_grants);
+    let grants = [h.grant(&source, 70), h.grant(&deposit, 30)];
+    let mut ix = client::transfer_checked(source, mint, deposit, &h.authority(), ciphertext(20), 0, 9, &grants);
+    ix.accounts.push(client::transfer_memo_account_meta());
+    h.send(&[memo, ix], &[]).unwrap();
+    assert_eq!(h.balance(&deposit), 30);
+
+    // Outgoing transfers are not affected
+    h.transfer(&mint, &deposit, &source, 5).unwrap();
+
+    let ix = client::disable_required_transfer_memos(deposit, &h.authority());
+    h.send(&[ix], &[]).unwrap();
+    h.transfer(&mint, &source, &deposit, 5).unwrap();
+    assert_eq!(h.balance(&deposit), 30);
+}
+
+// ========== ASSOCIATED TOKEN, MEMO AND METADATA ==========
+
+#[test]
+fn associated_token_account_is_created_idempotently() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(9);
+    let wallet = Pubkey::new_unique();
+    let address = client::get_associated_token_address(&wallet, &mint);
+
+    let ix = client::create(h.payer(), wallet, mint);
+    h.send(&[ix], &[]).unwrap();
+    h.mint_to(&mint, &address, 3);
+
+    let ix = client::create(h.payer(), wallet, mint);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::create_idempotent(h.payer(), wallet, mint);
+    h.send(&[ix], &[]).unwrap();
+
+    assert_eq!(h.account(&address).owner, wallet);
+    assert_eq!(h.balance(&address), 3);
+}
+
+#[test]
+fn build_memo_requires_all_signers() {
+    let mut h = Harness::new();
+    let cosigner = Keypair::new();
+
+    let ix = client::build_memo(h.payer(), ciphertext(42), 0, &[cosigner.pubkey()]);
+    h.send(&[ix], &[&cosigner]).unwrap();
+
+    let mut ix = client::build_memo(h.payer(), ciphertext(42), 0, &[cosigner.pubkey()]);
+    ix.accounts[2].is_signer = false;
+    assert!(h.send(&[ix], &[]).is_err());
+}
+
+#[test]
+fn build_memo_with_recipients_grants_them() {
+    let mut h = Harness::new();
+    let cosigner = Keypair::new();
+    let recipients = [Pubkey::new_unique(), cosigner.pubkey()];
+    let grants = recipients.map(|recipient| AllowanceGrant::new(42, recipient));
+
+    let ix = client::build_memo_with_recipients(h.payer(), ciphertext(42), 0, &[cosigner.pubkey()], &grants);
+    h.send(&[ix], &[&cosigner]).unwrap();
+    for recipient in &recipients {
+        assert!(h.svm.get_account(&client::allowance_address(Euint128(42), recipient)).is_some());
+    }
+
+    // An allowance PDA for another handle is rejected
+    let wrong = [AllowanceGrant::new(41, recipients[0])];
+    let ix = client::build_memo_with_recipients(h.payer(), ciphertext(43), 0, &[], &wrong);
+    assert!(h.send(&[ix], &[]).is_err());
+
+    // Every additional signer must sign, not only the leading ones
+    let other_cosigner = Keypair::new();
+    let grants = [AllowanceGrant::new(44, recipients[0])];
+    let mut ix = client::build_memo_with_recipients(
+        h.payer(), ciphertext(44), 0, &[cosigner.pubkey(), other_cosigner.pubkey()], &grants,
+    );
+    ix.accounts[4].is_signer = false;
+    assert!(h.send(&[ix], &[&cosigner]).is_err());
+}
+
+#[test]
+fn long_memo_chunks_are_granted_to_every_reader() {
+    let mut h = Harness::new();
+    let cosigner = Keypair::new();
+    let recipient = Pubkey::new_unique();
+
+    let chunks = client::memo_chunks(b"invoice 2024-118 for customer 7731");
+    assert_eq!(chunks.len(), 3);
+    let readers = [h.payer(), cosigner.pubkey(), recipient];
+    let grants: Vec<AllowanceGrant> = chunks
+        .iter()
+        .flat_map(|chunk| readers.iter().map(|reader| AllowanceGrant::new(*chunk, *reader)))
+        .collect();
+    let encrypted_chunks = chunks.iter().map(|chunk| ciphertext(*chunk)).collect();
+
+    let ix = client::build_long_memo(h.payer(), encrypted_chunks, 0, &[cosigner.pubkey()], Some(recipient), &grants);
+    h.send(&[ix], &[&cosigner]).unwrap();
+    for grant in &grants {
+        assert!(h.svm.get_account(&client::allowance_address(Euint128(grant.handle), &grant.allowed)).is_some());
+    }
+
+    // The recipient account is required
+    let mut ix = client::build_long_memo(h.payer(), vec![ciphertext(1)], 0, &[], Some(recipient), &[]);
+    ix.accounts.truncate(3);
+    assert!(h.send(&[ix], &[]).is_err());
+    let ix = client::build_long_memo(h.payer(), vec![], 0, &[], None, &[]);
+    assert!(h.send(&[ix], &[]).is_err());
+}
+
+#[test]
+fn metadata_lifecycle() {
+    let mut h = Harness::new();
+    let mint = h.create_mint(0);
+    let metadata = Keypair::new();
+    let creator = Keypair::new();
+
+    let args = CreateMetadataArgs {
+        name: "Lightning".to_string(),
+        symbol: "BOLT".to_string(),
+        uri: "https://example.com/bolt.json".to_string(),
+        seller_fee_basis_points: 500,
+        creators: Some(vec![Creator { address: creator.pubkey(), verified: false, share: 100 }]),
+        is_mutable: true,
+        collection: None,
+        uses: None,
+        collection_details: None,
+    };
+    let ix = client::create_metadata_account(metadata.pubkey(), mint, h.payer(), h.payer(), h.payer(), args);
+    h.send(&[ix], &[&metadata]).unwrap();
+
+    let args = UpdateMetadataArgs {
+        new_update_authority: None,
+        name: Some("Lightning Rod".to_string()),
+        symbol: None,
+        uri: None,
+        seller_fee_basis_points: None,
+        creators: None,
+        primary_sale_happened: Some(true),
+        is_mutable: None,
+        collection: CollectionToggle::None,
+        collection_details: CollectionDetailsToggle::None,
+        uses: UsesToggle::None,
+    };
+    let ix = client::update_metadata_account(metadata.pubkey(), h.payer(), args);
+    h.send(&[ix], &[]).unwrap();
+
+    let ix = client::sign_metadata(metadata.pubkey(), creator.pubkey());
+    h.send(&[ix], &[&creator]).unwrap();
+    let state = client::decode_metadata(&h.svm.get_account(&metadata.pubkey()).unwrap().data).unwrap();
+    assert_eq!(state.name, "Lightning Rod");
+    assert!(state.primary_sale_happened);
+    assert!(state.creators.unwrap()[0].verified);
+
+    let ix = client::remove_creator_verification(metadata.pubkey(), creator.pubkey());
+    h.send(&[ix], &[&creator]).unwrap();
+
+    let collection = Pubkey::new_unique();
+    let ix = client::set_and_verify_collection(metadata.pubkey(), h.payer(), Collection { verified: false, key: collection });
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::unverify_collection(metadata.pubkey(), h.payer());
+    h.send(&[ix], &[]).unwrap();
+    let ix = client::verify_collection(metadata.pubkey(), h.payer());
+    h.send(&[ix], &[]).unwrap();
+    let state = client::decode_metadata(&h.svm.get_account(&metadata.pubkey()).unwrap().data).unwrap();
+    assert!(!state.creators.unwrap()[0].verified);
+    assert_eq!(state.collection, Some(Collection { verified: true, key: collection }));
+
+    let master_edition = Keypair::new();
+    let ix = client::create_master_edition(
+        master_edition.pubkey(),
+        metadata.pubkey(),
+        mint,
+        h.payer(),
+        h.payer(),
+        h.payer(),
+        CreateMasterEditionArgs { max_supply: Some(1) },
+    );
+    h.send(&[ix], &[&master_edition]).unwrap();
+
+    let edition = Keypair::new();
+    let ix = client::print_edition(edition.pubkey(), master_edition.pubkey(), h.payer(), PrintEditionArgs { edition: 1 });
+    h.send(&[ix], &[&edition]).unwrap();
+    let printed = client::decode_edition(&h.svm.get_account(&edition.pubkey()).unwrap().data).unwrap();
+    assert_eq!(printed.parent, master_edition.pubkey());
+    let master = client::decode_master_edition(&h.svm.get_account(&master_edition.pubkey()).unwrap().data).unwrap();
+    assert_eq!(master.supply, 1);
+
+    let second = Keypair::new();
+    let ix = client::print_edition(second.pubkey(), master_edition.pubkey(), h.payer(), PrintEditionArgs { edition: 2 });
+    assert!(h.send(&[ix], &[&second]).is_err());
+}
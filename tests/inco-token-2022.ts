@@ -12,6 +12,8 @@ import { encryptValue } from "@inco/solana-sdk/encryption";
 import { decrypt } from "@inco/solana-sdk/attested-decrypt";
 import { hexToBuffer } from "@inco/solana-sdk/utils";
 
+const INCO_LIGHTNING_PROGRAM_ID = new PublicKey("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
+
 // Enhanced handle extraction function for Anchor BN objects
 function extractHandleFromAnchor(anchorHandle: any): string {
   if (anchorHandle && anchorHandle._bn) {
@@ -58,6 +60,29 @@ function extractHandleFromAnchor(anchorHandle: any): string {
   return "0";
 }
 
+// Allowance PDA letting allowedAddress decrypt handle
+function getAllowancePda(handle: string, allowedAddress: PublicKey): [PublicKey, number] {
+  const handleBuffer = Buffer.alloc(16);
+  let h = BigInt(handle);
+  for (let i = 0; i < 16; i++) {
+    handleBuffer[i] = Number(h & BigInt(0xff));
+    h = h >> BigInt(8);
+  }
+  return PublicKey.findProgramAddressSync(
+    [handleBuffer, allowedAddress.toBuffer()],
+    INCO_LIGHTNING_PROGRAM_ID
+  );
+}
+
+// [allowance account, allowed address] pair of remaining accounts
+function allowanceAccounts(handle: any, allowedAddress: PublicKey) {
+  const [allowancePda] = getAllowancePda(extractHandleFromAnchor(handle), allowedAddress);
+  return [
+    { pubkey: allowancePda, isSigner: false, isWritable: true },
+    { pubkey: allowedAddress, isSigner: false, isWritable: false },
+  ];
+}
+
 // Helper function to safely compare PublicKey objects
 function comparePublicKeys(actual: any, expected: PublicKey): boolean {
   if (!actual) return false;
@@ -149,6 +174,23 @@ describe("inco-token-2022", () => {
     }
   }
 
+  // Token accounts as the simulated transaction leaves them, to read the new
+  // handles that allowances must be granted for
+  async function simulateAndGetAccounts(tx: anchor.web3.Transaction, accountPubkeys: PublicKey[]): Promise<any[]> {
+    const { blockhash } = await connection.getLatestBlockhash();
+    tx.recentBlockhash = blockhash;
+    tx.feePayer = walletKeypair.publicKey;
+    tx.sign(walletKeypair);
+
+    const simulation = await connection.simulateTransaction(tx, undefined, accountPubkeys);
+    if (simulation.value.err) {
+      throw new Error(`Simulation failed: ${JSON.stringify(simulation.value.err)}`);
+    }
+    return simulation.value.accounts!.map(account =>
+      program.coder.accounts.decode("incoAccount", Buffer.from(account!.data[0], "base64"))
+    );
+  }
+
   describe("Token 2022 - Initialize Mint", () => {
     it("Should initialize a new mint with decimals validation", async () => {
       console.log("\n=== TOKEN 2022 - INITIALIZE MINT ===");
@@ -227,6 +269,16 @@ describe("inco-token-2022", () => {
       const mintAmount = BigInt(100000000);
       const encryptedHex = await encryptValue(mintAmount);
 
+      const txForSim = await program.methods
+        .mintToChecked(hexToBuffer(encryptedHex), inputType, 6)
+        .accounts({
+          mint: mintKeypair.publicKey,
+          account: ownerTokenAccountKp.publicKey,
+          authority: walletKeypair.publicKey,
+        } as any)
+        .transaction();
+      const [account] = await simulateAndGetAccounts(txForSim, [ownerTokenAccountKp.publicKey]);
+
       const tx = await program.methods
         .mintToChecked(hexToBuffer(encryptedHex), inputType, 6)
         .accounts({
@@ -234,6 +286,7 @@ describe("inco-token-2022", () => {
           account: ownerTokenAccountKp.publicKey,
           authority: walletKeypair.publicKey,
         } as any)
+        .remainingAccounts(allowanceAccounts(account.amount, walletKeypair.publicKey))
         .signers([])
         .rpc();
 
@@ -278,6 +331,20 @@ describe("inco-token-2022", () => {
 
       const encryptedHex = await encryptValue(BigInt(25000000));
 
+      const txForSim = await program.methods
+        .transferChecked(hexToBuffer(encryptedHex), inputType, 6)
+        .accounts({
+          source: ownerTokenAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
+          destination: recipientTokenAccountKp.publicKey,
+          authority: walletKeypair.publicKey,
+        } as any)
+        .transaction();
+      const [source, destination] = await simulateAndGetAccounts(txForSim, [
+        ownerTokenAccountKp.publicKey,
+        recipientTokenAccountKp.publicKey,
+      ]);
+
       const tx = await program.methods
         .transferChecked(hexToBuffer(encryptedHex), inputType, 6)
         .accounts({
@@ -286,6 +353,10 @@ describe("inco-token-2022", () => {
           destination: recipientTokenAccountKp.publicKey,
           authority: walletKeypair.publicKey,
         } as any)
+        .remainingAccounts([
+          ...allowanceAccounts(source.amount, walletKeypair.publicKey),
+          ...allowanceAccounts(destination.amount, walletKeypair.publicKey),
+        ])
         .signers([])
         .rpc();
 
@@ -307,6 +378,17 @@ describe("inco-token-2022", () => {
       
       const encryptedHex = await encryptValue(BigInt(10000000));
 
+      const txForSim = await program.methods
+        .approveChecked(hexToBuffer(encryptedHex), inputType, 6)
+        .accounts({
+          source: ownerTokenAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
+          delegate: walletKeypair.publicKey,
+          owner: walletKeypair.publicKey,
+        } as any)
+        .transaction();
+      const [source] = await simulateAndGetAccounts(txForSim, [ownerTokenAccountKp.publicKey]);
+
       const tx = await program.methods
         .approveChecked(hexToBuffer(encryptedHex), inputType, 6)
         .accounts({
@@ -315,6 +397,7 @@ describe("inco-token-2022", () => {
           delegate: walletKeypair.publicKey,
           owner: walletKeypair.publicKey,
         } as any)
+        .remainingAccounts(allowanceAccounts(source.delegatedAmount, walletKeypair.publicKey))
         .signers([])
         .rpc();
 
@@ -331,6 +414,16 @@ describe("inco-token-2022", () => {
 
       const encryptedHex = await encryptValue(BigInt(5000000));
 
+      const txForSim = await program.methods
+        .burnChecked(hexToBuffer(encryptedHex), inputType, 6)
+        .accounts({
+          account: ownerTokenAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
+          authority: walletKeypair.publicKey,
+        } as any)
+        .transaction();
+      const [account] = await simulateAndGetAccounts(txForSim, [ownerTokenAccountKp.publicKey]);
+
       const tx = await program.methods
         .burnChecked(hexToBuffer(encryptedHex), inputType, 6)
         .accounts({
@@ -338,6 +431,7 @@ describe("inco-token-2022", () => {
           mint: mintKeypair.publicKey,
           authority: walletKeypair.publicKey,
         } as any)
+        .remainingAccounts(allowanceAccounts(account.amount, walletKeypair.publicKey))
         .signers([])
         .rpc();
 
@@ -382,20 +476,27 @@ describe("inco-token-2022", () => {
 
       const destinationKeypair = Keypair.generate();
 
+      // Closing requires an attested decryption proving the balance is zero
+      const testAccount = await program.account.incoAccount.fetch(testAccountKp.publicKey);
+      const attestation = await decrypt([extractHandleFromAnchor(testAccount.amount)]);
+      const balancePlaintext = Buffer.alloc(16);
+      balancePlaintext.writeBigUInt64LE(BigInt(attestation.plaintexts[0]));
+
       const tx = await program.methods
-        .closeAccount2022()
+        .closeAccount2022(balancePlaintext)
         .accounts({
           account: testAccountKp.publicKey,
           destination: destinationKeypair.publicKey,
           authority: walletKeypair.publicKey,
         } as any)
+        .preInstructions(attestation.ed25519Instructions)
         .signers([])
         .rpc();
 
       console.log("Token 2022 close account transaction:", tx);
 
       const accountInfo = await provider.connection.getAccountInfo(testAccountKp.publicKey);
-      expect(accountInfo?.lamports || 0).to.equal(0);
+      expect(accountInfo).to.be.null;
     });
   });
 
@@ -93,6 +93,20 @@ describe("inco-token", () => {
     }
   }
 
+  // Authorities may be a multisig, so the IDL does not mark them as signers:
+  // flag each single-key signer on the instruction before sending it
+  async function signedTransaction(builder: any, signers: Keypair[]): Promise<anchor.web3.Transaction> {
+    const ix: anchor.web3.TransactionInstruction = await builder.instruction();
+    for (const key of ix.keys) {
+      if (signers.some(signer => signer.publicKey.equals(key.pubkey))) key.isSigner = true;
+    }
+    return new anchor.web3.Transaction().add(ix);
+  }
+
+  async function rpcWithSigners(builder: any, signers: Keypair[]): Promise<string> {
+    return provider.sendAndConfirm(await signedTransaction(builder, signers), signers);
+  }
+
   async function simulateAndGetHandle(tx: anchor.web3.Transaction, accountPubkey: PublicKey): Promise<bigint | null> {
     try {
       const { blockhash } = await connection.getLatestBlockhash();
@@ -152,6 +166,26 @@ describe("inco-token", () => {
     }
   }
 
+  // Token accounts as the simulated transaction leaves them, to read the new
+  // handles that allowances must be granted for
+  async function simulateAndGetAccounts(tx: anchor.web3.Transaction, accountPubkeys: PublicKey[]): Promise<any[] | null> {
+    try {
+      const { blockhash } = await connection.getLatestBlockhash();
+      tx.recentBlockhash = blockhash;
+      tx.feePayer = walletKeypair.publicKey;
+      tx.sign(walletKeypair);
+
+      const simulation = await connection.simulateTransaction(tx, undefined, accountPubkeys);
+      if (simulation.value.err) return null;
+
+      return simulation.value.accounts!.map(account =>
+        program.coder.accounts.decode("incoAccount", Buffer.from(account!.data[0], "base64"))
+      );
+    } catch {
+      return null;
+    }
+  }
+
   describe("Initialize", () => {
     it("Should initialize mint", async () => {
       const tx = await program.methods
@@ -253,6 +287,7 @@ describe("inco-token", () => {
         .transfer(hexToBuffer(encryptedHex), inputType)
         .accounts({
           source: ownerAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
           destination: recipientAccountKp.publicKey,
           authority: walletKeypair.publicKey,
           incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
@@ -271,6 +306,7 @@ describe("inco-token", () => {
         .transfer(hexToBuffer(encryptedHex), inputType)
         .accounts({
           source: ownerAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
           destination: recipientAccountKp.publicKey,
           authority: walletKeypair.publicKey,
           incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
@@ -304,6 +340,7 @@ describe("inco-token", () => {
         .transfer(hexToBuffer(encryptedHex), inputType)
         .accounts({
           source: ownerAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
           destination: ownerAccountKp.publicKey,
           authority: walletKeypair.publicKey,
           incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
@@ -313,6 +350,60 @@ describe("inco-token", () => {
 
       console.log("Self-transfer tx:", tx);
     });
+
+    it("Should batch transfer to multiple destinations", async () => {
+      const firstHex = await encryptValue(BigInt(10_000_000));
+      const secondHex = await encryptValue(BigInt(20_000_000));
+
+      const destinations = [
+        { pubkey: recipientAccountKp.publicKey, isSigner: false, isWritable: true },
+        { pubkey: delegateAccountKp.publicKey, isSigner: false, isWritable: true },
+      ];
+
+      const txForSim = await program.methods
+        .transferBatch([hexToBuffer(firstHex), hexToBuffer(secondHex)], inputType)
+        .accounts({
+          source: ownerAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
+          authority: walletKeypair.publicKey,
+          incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .remainingAccounts(destinations)
+        .transaction();
+
+      const simulated = await simulateAndGetAccounts(txForSim, [
+        ownerAccountKp.publicKey,
+        recipientAccountKp.publicKey,
+        delegateAccountKp.publicKey,
+      ]);
+      // [source grant, then one grant per destination], all owned by the wallet
+      const grants = simulated!.flatMap(account => {
+        const [allowancePda] = getAllowancePda(extractHandleFromAnchor(account.amount), walletKeypair.publicKey);
+        return [
+          { pubkey: allowancePda, isSigner: false, isWritable: true },
+          { pubkey: walletKeypair.publicKey, isSigner: false, isWritable: false },
+        ];
+      });
+
+      const tx = await program.methods
+        .transferBatch([hexToBuffer(firstHex), hexToBuffer(secondHex)], inputType)
+        .accounts({
+          source: ownerAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
+          authority: walletKeypair.publicKey,
+          incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .remainingAccounts([...destinations, ...grants])
+        .rpc();
+
+      console.log("Batch transfer tx:", tx);
+      await new Promise(r => setTimeout(r, 5000));
+
+      const destAccount = await program.account.incoAccount.fetch(recipientAccountKp.publicKey);
+      expect(extractHandleFromAnchor(destAccount.amount)).to.not.equal(BigInt(0));
+    });
   });
 
   describe("Burn", () => {
@@ -362,15 +453,35 @@ describe("inco-token", () => {
     it("Should approve delegate", async () => {
       const encryptedHex = await encryptValue(BigInt(100_000_000));
 
+      const txForSim = await program.methods
+        .approve(hexToBuffer(encryptedHex), inputType)
+        .accounts({
+          source: ownerAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
+          delegate: delegateAccountKp.publicKey,
+          owner: walletKeypair.publicKey,
+          incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .transaction();
+
+      const [source] = (await simulateAndGetAccounts(txForSim, [ownerAccountKp.publicKey]))!;
+      const [allowancePda] = getAllowancePda(extractHandleFromAnchor(source.delegatedAmount), delegateAccountKp.publicKey);
+
       const tx = await program.methods
         .approve(hexToBuffer(encryptedHex), inputType)
         .accounts({
           source: ownerAccountKp.publicKey,
+          mint: mintKeypair.publicKey,
           delegate: delegateAccountKp.publicKey,
           owner: walletKeypair.publicKey,
           incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         } as any)
+        .remainingAccounts([
+          { pubkey: allowancePda, isSigner: false, isWritable: true },
+          { pubkey: delegateAccountKp.publicKey, isSigner: false, isWritable: false },
+        ])
         .rpc();
 
       console.log("Approve tx:", tx);
@@ -378,6 +489,60 @@ describe("inco-token", () => {
       expect(account.delegate).to.have.property('some');
     });
 
+    it("Should spend delegated allowance on delegate transfer", async () => {
+      const before = await program.account.incoAccount.fetch(ownerAccountKp.publicKey);
+      const encryptedHex = await encryptValue(BigInt(50_000_000));
+
+      const txForSim = await signedTransaction(
+        program.methods
+          .transfer(hexToBuffer(encryptedHex), inputType)
+          .accounts({
+            source: ownerAccountKp.publicKey,
+            mint: mintKeypair.publicKey,
+            destination: recipientAccountKp.publicKey,
+            authority: delegateAccountKp.publicKey,
+            incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          } as any),
+        [delegateAccountKp]
+      );
+
+      const [source, destination] = (await simulateAndGetAccounts(txForSim, [
+        ownerAccountKp.publicKey,
+        recipientAccountKp.publicKey,
+      ]))!;
+      const delegatedHandle = extractHandleFromAnchor(source.delegatedAmount);
+      const grant = (handle: bigint, allowed: PublicKey) => [
+        { pubkey: getAllowancePda(handle, allowed)[0], isSigner: false, isWritable: true },
+        { pubkey: allowed, isSigner: false, isWritable: false },
+      ];
+
+      const tx = await rpcWithSigners(
+        program.methods
+          .transfer(hexToBuffer(encryptedHex), inputType)
+          .accounts({
+            source: ownerAccountKp.publicKey,
+            mint: mintKeypair.publicKey,
+            destination: recipientAccountKp.publicKey,
+            authority: delegateAccountKp.publicKey,
+            incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .remainingAccounts([
+            ...grant(extractHandleFromAnchor(source.amount), walletKeypair.publicKey),
+            ...grant(extractHandleFromAnchor(destination.amount), walletKeypair.publicKey),
+            ...grant(delegatedHandle, walletKeypair.publicKey),
+            ...grant(delegatedHandle, delegateAccountKp.publicKey),
+          ]),
+        [delegateAccountKp]
+      );
+
+      console.log("Delegate transfer tx:", tx);
+      const after = await program.account.incoAccount.fetch(ownerAccountKp.publicKey);
+      expect(extractHandleFromAnchor(after.delegatedAmount))
+        .to.not.equal(extractHandleFromAnchor(before.delegatedAmount));
+    });
+
     it("Should revoke delegate", async () => {
       const tx = await program.methods
         .revoke()
@@ -418,6 +583,7 @@ describe("inco-token", () => {
           .transfer(hexToBuffer(encryptedHex), inputType)
           .accounts({
             source: ownerAccountKp.publicKey,
+            mint: mintKeypair.publicKey,
             destination: recipientAccountKp.publicKey,
             authority: walletKeypair.publicKey,
             incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
@@ -446,6 +612,123 @@ describe("inco-token", () => {
     });
   });
 
+  describe("Set Authority", () => {
+    it("Should set close authority via set_authority", async () => {
+      const tx = await program.methods
+        .setAuthority({ closeAccount: {} }, walletKeypair.publicKey)
+        .accounts({
+          owned: recipientAccountKp.publicKey,
+          currentAuthority: walletKeypair.publicKey,
+        } as any)
+        .rpc();
+
+      console.log("Set authority tx:", tx);
+      const account = await program.account.incoAccount.fetch(recipientAccountKp.publicKey);
+      expect(account.closeAuthority).to.have.property('some');
+    });
+
+    it("Should reject mint authority type on a token account", async () => {
+      try {
+        await program.methods
+          .setAuthority({ mintTokens: {} }, walletKeypair.publicKey)
+          .accounts({
+            owned: recipientAccountKp.publicKey,
+            currentAuthority: walletKeypair.publicKey,
+          } as any)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.toString()).to.include("AuthorityTypeNotSupported");
+      }
+    });
+  });
+
+  describe("Auditor", () => {
+    const auditorKp = Keypair.generate();
+
+    it("Should set the mint auditor", async () => {
+      const tx = await program.methods
+        .setAuditor(auditorKp.publicKey)
+        .accounts({
+          mint: mintKeypair.publicKey,
+          mintAuthority: walletKeypair.publicKey,
+        } as any)
+        .rpc();
+
+      console.log("Set auditor tx:", tx);
+      const mintAccount = await program.account.incoMint.fetch(mintKeypair.publicKey);
+      expect(mintAccount.auditor).to.have.property('some');
+    });
+
+    it("Should reject a transfer without auditor allowance accounts", async () => {
+      const encryptedHex = await encryptValue(BigInt(1_000_000));
+      try {
+        await program.methods
+          .transfer(hexToBuffer(encryptedHex), inputType)
+          .accounts({
+            source: ownerAccountKp.publicKey,
+            mint: mintKeypair.publicKey,
+            destination: recipientAccountKp.publicKey,
+            authority: walletKeypair.publicKey,
+            incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.toString()).to.include("MissingAuditorAllowance");
+      }
+    });
+
+    it("Should clear the mint auditor", async () => {
+      await program.methods
+        .setAuditor(null)
+        .accounts({
+          mint: mintKeypair.publicKey,
+          mintAuthority: walletKeypair.publicKey,
+        } as any)
+        .rpc();
+
+      const mintAccount = await program.account.incoMint.fetch(mintKeypair.publicKey);
+      expect(mintAccount.auditor).to.have.property('none');
+    });
+  });
+
+  describe("Max Supply", () => {
+    it("Should cap the mint supply", async () => {
+      const tx = await program.methods
+        .setMaxSupply({ plaintext: { 0: new anchor.BN("1000000000000000") } })
+        .accounts({
+          mint: mintKeypair.publicKey,
+          mintAuthority: walletKeypair.publicKey,
+          incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        } as any)
+        .rpc();
+
+      console.log("Set max supply tx:", tx);
+      const mintAccount = await program.account.incoMint.fetch(mintKeypair.publicKey);
+      expect(mintAccount.maxSupply).to.have.property('some');
+    });
+
+    it("Should reject setting the max supply twice", async () => {
+      try {
+        await program.methods
+          .setMaxSupply({ plaintext: { 0: new anchor.BN(1) } })
+          .accounts({
+            mint: mintKeypair.publicKey,
+            mintAuthority: walletKeypair.publicKey,
+            incoLightningProgram: INCO_LIGHTNING_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          } as any)
+          .rpc();
+        expect.fail("Should have thrown");
+      } catch (error: any) {
+        expect(error.toString()).to.include("MaxSupplyAlreadySet");
+      }
+    });
+  });
+
   describe("Summary", () => {
     it("Should display final balances", async () => {
       console.log("\n=== Final Balances ===");
@@ -0,0 +1,148 @@
+#![allow(unexpected_cfgs)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
+
+// Same address as Inco Lightning so that inco_token's CPIs land here when the
+// offline harness loads this program in its place
+declare_id!("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
+
+// ========== SHARED TYPES ==========
+
+/// Encrypted u128 handle. In the mock a handle is its own plaintext.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Euint128(pub u128);
+
+/// Encrypted bool handle: 1 for true, 0 for false
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Ebool(pub u128);
+
+#[program]
+pub mod mock_inco_lightning {
+    use super::*;
+
+    /// The "ciphertext" is the little-endian plaintext, up to 16 bytes
+    pub fn new_euint128(_ctx: Context<Operation>, ciphertext: Vec<u8>, _input_type: u8) -> Result<Euint128> {
+        require!(ciphertext.len() <= 16, MockIncoError::InvalidCiphertext);
+        let mut value = [0u8; 16];
+        value[..ciphertext.len()].copy_from_slice(&ciphertext);
+        Ok(Euint128(u128::from_le_bytes(value)))
+    }
+
+    pub fn as_euint128(_ctx: Context<Operation>, value: u128) -> Result<Euint128> {
+        Ok(Euint128(value))
+    }
+
+    pub fn e_add(_ctx: Context<Operation>, lhs: Euint128, rhs: Euint128, _scalar_byte: u8) -> Result<Euint128> {
+        Ok(Euint128(lhs.0.wrapping_add(rhs.0)))
+    }
+
+    pub fn e_sub(_ctx: Context<Operation>, lhs: Euint128, rhs: Euint128, _scalar_byte: u8) -> Result<Euint128> {
+        Ok(Euint128(lhs.0.wrapping_sub(rhs.0)))
+    }
+
+    pub fn e_mul(_ctx: Context<Operation>, lhs: Euint128, rhs: Euint128, _scalar_byte: u8) -> Result<Euint128> {
+        Ok(Euint128(lhs.0.wrapping_mul(rhs.0)))
+    }
+
+    pub fn e_shr(_ctx: Context<Operation>, lhs: Euint128, rhs: Euint128, _scalar_byte: u8) -> Result<Euint128> {
+        Ok(Euint128(lhs.0.checked_shr(rhs.0 as u32).unwrap_or(0)))
+    }
+
+    pub fn e_ge(_ctx: Context<Operation>, lhs: Euint128, rhs: Euint128, _scalar_byte: u8) -> Result<Ebool> {
+        Ok(Ebool((lhs.0 >= rhs.0) as u128))
+    }
+
+    pub fn e_eq(_ctx: Context<Operation>, lhs: Euint128, rhs: Euint128, _scalar_byte: u8) -> Result<Ebool> {
+        Ok(Ebool((lhs.0 == rhs.0) as u128))
+    }
+
+    pub fn e_select(
+        _ctx: Context<Operation>,
+        condition: Ebool,
+        if_true: Euint128,
+        if_false: Euint128,
+        _scalar_byte: u8,
+    ) -> Result<Euint128> {
+        Ok(if condition.0 != 0 { if_true } else { if_false })
+    }
+
+    /// Create the allowance PDA [handle, allowed] and record `value` in it
+    pub fn allow(ctx: Context<Allow>, handle: u128, value: bool, allowed: Pubkey) -> Result<()> {
+        require!(ctx.accounts.allowed_address.key() == allowed, MockIncoError::InvalidAllowanceAccount);
+
+        let handle_bytes = handle.to_le_bytes();
+        let (expected, bump) = Pubkey::find_program_address(&[&handle_bytes, allowed.as_ref()], &crate::ID);
+        require!(ctx.accounts.allowance_account.key() == expected, MockIncoError::InvalidAllowanceAccount);
+
+        // Equal plaintexts share a handle, so the same allowance can be granted twice
+        if ctx.accounts.allowance_account.lamports() == 0 {
+            let bump = [bump];
+            let seeds: &[&[u8]] = &[&handle_bytes, allowed.as_ref(), &bump];
+            let signer_seeds = &[seeds];
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.signer.to_account_info(),
+                    to: ctx.accounts.allowance_account.to_account_info(),
+                },
+                signer_seeds,
+            );
+            create_account(cpi_ctx, Rent::get()?.minimum_balance(1), 1, &crate::ID)?;
+        }
+        ctx.accounts.allowance_account.try_borrow_mut_data()?[0] = value as u8;
+
+        Ok(())
+    }
+
+    /// Attestations are not signed in the mock: each plaintext must equal its handle
+    pub fn is_validsignature(
+        _ctx: Context<VerifySignature>,
+        expected_signature_count: u8,
+        handles: Option<Vec<Vec<u8>>>,
+        plaintext_values: Option<Vec<Vec<u8>>>,
+    ) -> Result<bool> {
+        let handles = handles.unwrap_or_default();
+        let plaintext_values = plaintext_values.unwrap_or_default();
+        require!(handles.len() == expected_signature_count as usize, MockIncoError::InvalidAttestation);
+        require!(handles == plaintext_values, MockIncoError::InvalidAttestation);
+        Ok(true)
+    }
+}
+
+// ========== ACCOUNT CONTEXTS ==========
+
+#[derive(Accounts)]
+pub struct Operation<'info> {
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Allow<'info> {
+    /// CHECK: Allowance PDA, derived and created in the instruction
+    #[account(mut)]
+    pub allowance_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// CHECK: Address being granted access
+    pub allowed_address: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifySignature<'info> {
+    /// CHECK: Instructions sysvar, unused by the mock
+    pub instructions: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+}
+
+// ========== ERROR CODES ==========
+#[error_code]
+pub enum MockIncoError {
+    #[msg("Ciphertext longer than 16 bytes")]
+    InvalidCiphertext,
+    #[msg("Allowance account does not match the handle and allowed address")]
+    InvalidAllowanceAccount,
+    #[msg("Plaintext does not match the handle")]
+    InvalidAttestation,
+}
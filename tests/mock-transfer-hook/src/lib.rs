@@ -0,0 +1,44 @@
+#![allow(unexpected_cfgs)]
+
+use anchor_lang::solana_program::account_info::AccountInfo;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_error::ProgramError;
+use anchor_lang::solana_program::pubkey::Pubkey;
+use anchor_lang::solana_program::{declare_id, msg};
+
+declare_id!("4gYFE9BuUL3gXb8VNztp1eSRvyfzw6DN4sbCEE2Aj9wA");
+
+/// First 8 bytes of sha256("inco-transfer-hook-interface:execute")
+pub const EXECUTE_DISCRIMINATOR: [u8; 8] = [80, 224, 187, 159, 205, 172, 116, 125];
+/// Largest amount the hook lets through. Inco Lightning handles are plaintext
+/// under the offline mock, so the hook can read the amount directly.
+pub const MAX_TRANSFER: u128 = 100;
+
+#[cfg(not(feature = "no-entrypoint"))]
+anchor_lang::solana_program::entrypoint!(process_instruction);
+
+/// Execute accounts: [source, mint, destination, authority, extra_account_metas, ...]
+/// Execute data: EXECUTE_DISCRIMINATOR followed by the u128 amount handle (LE)
+pub fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data.len() != 24 || data[..8] != EXECUTE_DISCRIMINATOR {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if accounts.len() < 5 {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    let mint = accounts[1].key;
+    let (extra_account_metas, _) = Pubkey::find_program_address(&[b"extra-account-metas", mint.as_ref()], program_id);
+    if *accounts[4].key != extra_account_metas {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut amount = [0u8; 16];
+    amount.copy_from_slice(&data[8..]);
+    let amount = u128::from_le_bytes(amount);
+    if amount > MAX_TRANSFER {
+        msg!("Transfer of {} exceeds the hook limit", amount);
+        return Err(ProgramError::Custom(0));
+    }
+    Ok(())
+}